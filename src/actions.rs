@@ -3,10 +3,12 @@ use std::{env, path::PathBuf, process::Command};
 use colored::Colorize;
 use git2::Repository;
 
-use crate::{completion::model::Shell, ErrConversion};
+use crate::{cmd::VcsKind, completion::model::Shell, ErrConversion};
 use crate::console::command::CommandExt;
 use crate::utils::error::ErrorExt;
 use crate::utils::file::PathExt;
+use crate::utils::messages::Messages;
+use crate::utils::privilege::Privilege;
 
 use super::data::InitializrData;
 use crate::git::Git;
@@ -25,15 +27,36 @@ impl<'a> ProjectActions<'a> {
     /// Do optional stuffs after project bootstrapped
     pub fn apply(&self) -> anyhow::Result<()> {
         env::set_current_dir(self.path)?;
-        self.git_init()
-            .map(|r| self.git_add_ignore(r))
-            .warn(format!("Failed initializing git in {}", format!("{:?}", self.path).as_str().yellow()));
+        self.init_vcs();
+        // Chown here too, before `then_execute_commands` gets a chance to call
+        // `Privilege::drop_to_real_user()` below (a one-way `seteuid()` with no re-elevation
+        // anywhere in this codebase): once that runs, `.git` and anything `init_vcs` created
+        // can no longer be chowned back to the real user
+        Privilege::chown_to_real_user(self.path);
         self.then_execute_commands();
         self.idea_open();
         self.vs_code_open();
+        // Chown again: when `data.commands` was empty, privileges were never dropped above,
+        // so `idea_open`/`vs_code_open` still ran elevated and may have created artifacts of
+        // their own. When commands did run, privileges are already down and this is a no-op.
+        Privilege::chown_to_real_user(self.path);
         self.erase_if_dry()
     }
 
+    /// Initializes the `--vcs`-selected backend, warning and skipping rather than failing
+    /// the whole bootstrap when the chosen tool isn't available
+    fn init_vcs(&self) {
+        match self.data.vcs() {
+            VcsKind::Git => {
+                self.git_init()
+                    .map(|r| self.git_add_ignore(r))
+                    .warn_msg("git_init_failed", &[&format!("{:?}", self.path)]);
+            }
+            VcsKind::Hg => self.hg_init(),
+            VcsKind::None => {}
+        }
+    }
+
     fn git_init(&self) -> anyhow::Result<Repository> {
         Git::init(self.path)
             .then(|_| info!("Git initialized in {}", format!("{:?}", self.path).as_str().cyan()))
@@ -49,6 +72,21 @@ impl<'a> ProjectActions<'a> {
         }
     }
 
+    fn hg_init(&self) {
+        if !Self::is_available("hg") {
+            warn!("{}", Messages::get().render("vcs_unavailable", &["hg", &format!("{:?}", self.path)]));
+            return;
+        }
+        Command::new("hg").arg("init").current_dir(self.path).no_output().spawn_and_wait()
+            .then(|_| info!("Hg initialized in {}", format!("{:?}", self.path).as_str().cyan()))
+            .warn_msg("hg_init_failed", &[&format!("{:?}", self.path)]);
+    }
+
+    /// Whether `bin` resolves to a runnable binary on `PATH`
+    fn is_available(bin: &str) -> bool {
+        Command::new(bin).arg("--version").no_output().spawn_and_wait().is_ok()
+    }
+
     fn idea_open(&self) {
         if self.data.is_idea() {
             info!("Opening in IntelliJ with 'idea' cli in {}", format!("{:?}", self.path).as_str().cyan());
@@ -69,14 +107,34 @@ impl<'a> ProjectActions<'a> {
     }
 
     fn then_execute_commands(&self) {
+        if !self.data.commands.is_empty() {
+            Privilege::drop_to_real_user();
+        }
         self.data.commands.iter().for_each(|cmd| {
-            info!("Executing '{}' in {}", cmd.as_str().cyan(), format!("{:?}", self.path).as_str().cyan());
-            Shell::run(cmd, self.path)
-                .then(|(_, stdout, _)| println!("{}", stdout))
-                .warn(format!("Failed executing {} in {}", cmd.as_str().yellow(), format!("{:?}", self.path).as_str().yellow()));
+            match self.data.container_image() {
+                Some(image) => self.execute_in_container(cmd, image),
+                None => self.execute_on_host(cmd),
+            }
         })
     }
 
+    fn execute_on_host(&self, cmd: &str) {
+        info!("Executing '{}' in {}", cmd.as_str().cyan(), format!("{:?}", self.path).as_str().cyan());
+        Shell::run(cmd, self.path)
+            .then(|(_, stdout, _)| println!("{}", stdout))
+            .warn(format!("Failed executing {} in {}", cmd.as_str().yellow(), format!("{:?}", self.path).as_str().yellow()));
+    }
+
+    /// Bind-mounts the generated project into `image` at `/work` and runs `cmd` there via
+    /// 'docker run', so template authors can validate it in a clean toolchain image
+    fn execute_in_container(&self, cmd: &str, image: &str) {
+        info!("Executing '{}' in container {} mounting {}", cmd.as_str().cyan(), image.cyan(), format!("{:?}", self.path).as_str().cyan());
+        Command::new("docker")
+            .args(&["run", "--rm", "-v", &format!("{}:/work", self.path.path_str()), "-w", "/work", image, "sh", "-c", cmd])
+            .spawn_and_wait()
+            .warn(format!("Failed executing {} in container {}", cmd.as_str().yellow(), image.yellow()));
+    }
+
     fn erase_if_dry(&self) -> anyhow::Result<()> {
         if self.data.is_dry() {
             info!("Removing generated project {}", format!("{:?}", self.path).as_str().cyan());
@@ -17,6 +17,20 @@ pub struct Cli {
     pub cmd: Option<SubCommand>,
     #[clap(flatten)]
     pub log: Log,
+    #[clap(flatten)]
+    pub confirm: Confirm,
+    /// Controls colored output: 'auto' colors only when stdout/stderr are a TTY and
+    /// NO_COLOR/TERM=dumb aren't set, 'always' forces it, 'never' strips it
+    #[clap(long, global = true, default_value = "auto")]
+    pub color: ColorMode,
+    /// Generates a completion script for the fully assembled app (zr + every template's
+    /// dynamic args) and prints it to stdout. Meant for packaging scripts, hence hidden
+    #[clap(long, hidden = true)]
+    pub generate_completions: Option<Shell>,
+    /// Generates a roff man page for the fully assembled app and prints it to stdout.
+    /// Meant for packaging scripts, hence hidden
+    #[clap(long, hidden = true)]
+    pub generate_man: bool,
 }
 
 #[derive(Clap, Debug)]
@@ -34,6 +48,11 @@ pub enum SubCommand {
     Completion {
         #[clap(subcommand)]
         shell: Option<Shell>,
+        /// Writes the generated script to standard output instead of installing it
+        ///
+        /// e.g. `zr completion --stdout zsh > ~/.zfunc/_zr`
+        #[clap(long)]
+        stdout: bool,
     },
     New {
         #[clap(flatten)]
@@ -43,6 +62,93 @@ pub enum SubCommand {
     ///
     /// Use it like `code $(zr get-config)`
     GetConfig,
+    /// Manages remote template repositories
+    Repo {
+        #[clap(subcommand)]
+        action: RepoCommand,
+    },
+}
+
+#[derive(Clap, Debug)]
+#[clap(rename_all = "kebab-case")]
+pub enum RepoCommand {
+    /// Registers a new remote template repository and fetches it
+    Add { url: String },
+    /// Unregisters a remote template repository
+    Remove { url: String },
+    /// Lists configured repositories, their templates and local cache status
+    List,
+}
+
+/// Mirrors rustc's `ColorConfig`: whether to colorize the terminal output produced by
+/// `colored` (Asker's prompts, ZrUpgrade's info output) and by env_logger's formatter.
+#[derive(Clap, Debug, Copy, Clone, Eq, PartialEq)]
+#[clap(rename_all = "kebab-case")]
+pub enum ColorMode {
+    /// Colors only when stdout/stderr are a TTY and NO_COLOR/TERM=dumb aren't set
+    Auto,
+    /// Always colors, even when piped or redirected
+    Always,
+    /// Never colors
+    Never,
+}
+
+impl ColorMode {
+    /// Resolves this mode to a yes/no decision, applying the 'auto' heuristic when needed
+    pub fn should_colorize(&self) -> bool {
+        match self {
+            Self::Always => true,
+            Self::Never => false,
+            Self::Auto => Self::is_tty_and_not_dumb(),
+        }
+    }
+
+    /// Globally overrides `colored`'s own TTY detection with the resolved decision
+    pub fn apply(&self) {
+        colored::control::set_override(self.should_colorize());
+    }
+
+    /// Maps to the `env_logger` equivalent, so log formatting follows the same decision
+    pub fn write_style(&self) -> env_logger::WriteStyle {
+        if self.should_colorize() {
+            env_logger::WriteStyle::Always
+        } else {
+            env_logger::WriteStyle::Never
+        }
+    }
+
+    fn is_tty_and_not_dumb() -> bool {
+        use atty::Stream;
+        let no_color = std::env::var("NO_COLOR").is_ok();
+        let dumb_term = std::env::var("TERM").map(|it| it == "dumb").unwrap_or_default();
+        !no_color && !dumb_term && atty::is(Stream::Stdout) && atty::is(Stream::Stderr)
+    }
+}
+
+impl std::str::FromStr for ColorMode {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        match raw.to_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "always" => Ok(Self::Always),
+            "never" => Ok(Self::Never),
+            other => Err(format!("Unknown color mode '{}'", other)),
+        }
+    }
+}
+
+/// Pre-seeds every yes/no question asked throughout the run, so it never blocks on stdin.
+/// See [`crate::console::non_interactive::NonInteractive`].
+#[derive(Clap, Debug)]
+#[clap(rename_all = "kebab-case")]
+pub struct Confirm {
+    /// Answers 'yes' to every yes/no question instead of prompting
+    #[clap(long, global = true, conflicts_with = "no")]
+    pub yes: bool,
+    /// Answers 'no' to every yes/no question instead of prompting
+    #[clap(long, global = true)]
+    pub no: bool,
 }
 
 #[derive(Clap, Debug)]
@@ -85,15 +191,40 @@ mod cli_tests {
         zr(&["--warning", "help"]);
     }
 
+    #[test]
+    fn confirm() {
+        zr(&["--yes", "help"]);
+        zr(&["--no", "help"]);
+        zr_fail(&["--yes", "--no", "help"]);
+    }
+
+    #[test]
+    fn color() {
+        zr(&["--color", "auto", "help"]);
+        zr(&["--color", "always", "help"]);
+        zr(&["--color", "never", "help"]);
+        zr_fail(&["--color", "rainbow", "help"]);
+    }
+
     #[test]
     fn commands() {
         zr(&["completion", "-h"]);
         zr(&["new", "-h"]);
         zr(&["get-config", "-h"]);
         zr(&["upgrade", "-h"]);
+        zr(&["repo", "-h"]);
         zr_fail(&["unknown", "-h"]);
     }
 
+    #[test]
+    fn should_match_repo() {
+        zr(&["repo", "-h"]);
+        zr(&["repo", "add", "-h"]);
+        zr(&["repo", "remove", "-h"]);
+        zr(&["repo", "list", "-h"]);
+        zr_fail(&["repo", "unknown", "-h"]);
+    }
+
     #[test]
     fn should_match_completion() {
         zr(&["completion", "-h"]);
@@ -103,4 +234,9 @@ mod cli_tests {
         zr(&["completion", "elvish", "-h"]);
         zr_fail(&["completion", "unknown", "-h"]);
     }
+
+    #[test]
+    fn should_match_completion_stdout_flag() {
+        zr(&["completion", "--stdout", "zsh", "-h"]);
+    }
 }
\ No newline at end of file
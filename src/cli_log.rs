@@ -3,7 +3,7 @@ use std::{env, str::FromStr};
 use env_logger::Builder;
 use log::LevelFilter;
 
-use crate::cli::Log;
+use crate::cli::{ColorMode, Log};
 
 /// Sets log level for current process
 pub struct CliLog;
@@ -21,7 +21,9 @@ impl CliLog {
     /// Inits log level globally
     /// If debug we set RUST_BACKTRACE for current process to get errors stacktrace
     /// Cli's log level can also be configured globally with RUST_LOG env var
-    pub fn init(log: &Log) {
+    /// Also resolves `color` and applies it to both `colored` and env_logger's formatting
+    pub fn init(log: &Log, color: &ColorMode) {
+        color.apply();
         let log_level = Self::match_log_level(log);
         env::set_var(Self::LOG_LEVEL, format!("{}", log_level));
         let is_log_level_debug = log_level == LevelFilter::Debug;
@@ -29,6 +31,7 @@ impl CliLog {
         Builder::from_default_env()
             .format_timestamp(None)
             .filter_level(log_level)
+            .write_style(color.write_style())
             .format_level(is_log_level_debug)
             .format_module_path(is_log_level_debug)
             .init()
@@ -1,3 +1,4 @@
+use atty::Stream;
 use clap::{App, AppSettings, Arg, Clap};
 use field_types::FieldName;
 use itertools::Itertools;
@@ -50,19 +51,90 @@ pub struct InitializrStdArgs {
     /// If not present, user permission is asked
     #[clap(short, long)]
     pub force: bool,
+    /// Prints the resolved arguments as JSON instead of generating the project
+    ///
+    /// Useful to debug a template's placeholders: shows exactly which defaults, flags,
+    /// negated flags and multi-args were computed before anything is written to disk
+    #[clap(long)]
+    pub show_context: bool,
+    /// Checks an already-generated project for drift against the current template instead
+    /// of writing anything
+    ///
+    /// Renders every template into a throwaway directory and diffs it byte-for-byte against
+    /// the existing project, reporting missing, extra and changed files. Exits non-zero when
+    /// drift is found, leaving both trees untouched. Meant for running 'zr <lang> <kind>
+    /// --verify' in CI to catch a generated project that has diverged from its template
+    #[clap(long)]
+    pub verify: bool,
+    /// Prompts for any missing argument instead of falling back to its default
+    ///
+    /// Offers the declared default as the pre-filled answer, a select list for enum-typed
+    /// args, and a yes/no question for flags. Has no effect outside of a TTY
+    #[clap(short, long)]
+    pub interactive: bool,
+    /// Runs every template command in a container instead of on the host
+    ///
+    /// The generated project is bind-mounted into the given image at `/work` and each
+    /// command runs there via 'docker run'. Pairs well with '--dry': validate the project
+    /// in a clean toolchain image, then discard it
+    #[clap(long)]
+    pub container: Option<String>,
+    /// Chooses the version-control backend initialized in the generated project
+    ///
+    /// 'git' is the default and also adds any generated '.gitignore' to the index. 'hg' runs
+    /// 'hg init'. 'none' skips VCS initialization entirely. Whichever is picked, its
+    /// availability is checked at runtime and initialization is warned about and skipped,
+    /// rather than failing the whole bootstrap, when the backend's binary is missing
+    #[clap(long, default_value = "git")]
+    pub vcs: VcsKind,
+}
+
+/// The version-control backend to initialize in a freshly generated project,
+/// see [`InitializrStdArgs::vcs`]
+#[derive(Clap, Debug, Clone, Copy, Serialize, Eq, PartialEq)]
+#[clap(rename_all = "kebab-case")]
+pub enum VcsKind {
+    Git,
+    Hg,
+    None,
+}
+
+impl Default for VcsKind {
+    fn default() -> Self { Self::Git }
+}
+
+impl std::str::FromStr for VcsKind {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        match raw.to_lowercase().as_str() {
+            "git" => Ok(Self::Git),
+            "hg" => Ok(Self::Hg),
+            "none" => Ok(Self::None),
+            other => Err(format!("Unknown vcs '{}'", other)),
+        }
+    }
 }
 
 impl InitializrStdArgs {
     pub(crate) const PROJECT_NAME_ARG_NAME: &'static str = "project-name";
     const PROJECT_NAME_ABOUT: &'static str = "name of generated project";
+    const INTERACTIVE_FLAGS: [&'static str; 2] = ["-i", "--interactive"];
 
     pub fn project_name_arg<'a>() -> Arg<'a> {
         Arg::new(Self::PROJECT_NAME_ARG_NAME)
             .about(Self::PROJECT_NAME_ABOUT)
-            .required(true)
+            .required(!Self::is_interactive())
             .takes_value(true)
     }
 
+    /// Whether `--interactive`/`-i` was passed on the command line.
+    /// Checked ahead of clap parsing so the project name positional can be relaxed
+    /// from `required` before prompting for it when missing.
+    pub fn is_interactive() -> bool {
+        atty::is(Stream::Stdin) && std::env::args().any(|it| Self::INTERACTIVE_FLAGS.contains(&it.as_str()))
+    }
+
     pub fn variants() -> Vec<&'static str> {
         Self::as_field_name_array().iter()
             .map(|it| it.name())
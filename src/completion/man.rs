@@ -0,0 +1,86 @@
+//! Minimal roff man-page renderer for a `clap::App`, walking its `Arg`s the same way
+//! [`super::app::ZrApp`] walks them to build completions, grouping `NamedArg`-produced
+//! `CMD` args under their `COMMANDS` help heading the way ripgrep builds its manpage
+//! from its own arg table.
+use std::io::{self, Write};
+
+use clap::{App, Arg};
+use colored::Colorize;
+
+use crate::template::local_arg::NamedArg;
+use crate::utils::error::ErrorExt;
+use crate::utils::file::PathExt;
+use crate::utils::user::User;
+
+pub struct ManPage;
+
+impl ManPage {
+    const MAN_DIR: &'static str = ".local/share/man/man1";
+
+    pub fn generate(app: App, stdout: bool) {
+        let bin_name = app.get_name().to_string();
+        let roff = Self::render(&app);
+        if stdout {
+            let _ = io::stdout().write_all(roff.as_bytes());
+            return;
+        }
+        let dir = User::home().map(|it| it.join(Self::MAN_DIR)).fail("Could not find home dir");
+        if !dir.exists() {
+            dir.create_dir_all().fail(format!("Failed creating man page folder {:?}", dir));
+        }
+        let path = dir.join(format!("{}.1", bin_name));
+        path.write_to(&roff);
+        info!("Generated man page for {} in {}", bin_name.as_str().green(), format!("{:?}", path).as_str().green());
+    }
+
+    fn render(app: &App) -> String {
+        let bin_name = app.get_name();
+        let mut roff = format!(".TH {} 1\n.SH NAME\n{}", bin_name.to_uppercase(), bin_name);
+        match app.get_about() {
+            Some(about) => roff.push_str(&format!(" \\- {}\n", about)),
+            None => roff.push('\n'),
+        }
+        roff.push_str(&format!(".SH SYNOPSIS\n.B {}\n[OPTIONS]\n", bin_name));
+        roff.push_str(".SH OPTIONS\n");
+        app.get_arguments()
+            .filter(|arg| arg.get_help_heading() != Some(NamedArg::CMD_HELP_HEADING))
+            .for_each(|arg| Self::render_arg(&mut roff, arg));
+        let commands = app.get_arguments()
+            .filter(|arg| arg.get_help_heading() == Some(NamedArg::CMD_HELP_HEADING))
+            .collect::<Vec<_>>();
+        if !commands.is_empty() {
+            roff.push_str(&format!(".SH {}\n", NamedArg::CMD_HELP_HEADING));
+            commands.into_iter().for_each(|arg| Self::render_arg(&mut roff, arg));
+        }
+        if app.get_subcommands().next().is_some() {
+            roff.push_str(".SH SUBCOMMANDS\n");
+            app.get_subcommands().for_each(|sub| Self::render_subcommand(&mut roff, sub));
+        }
+        roff
+    }
+
+    fn render_arg(roff: &mut String, arg: &Arg) {
+        let mut flags = vec![];
+        if let Some(short) = arg.get_short() {
+            flags.push(format!("\\-{}", short));
+        }
+        if let Some(long) = arg.get_long() {
+            flags.push(format!("\\-\\-{}", long));
+        }
+        roff.push_str(&format!(".TP\n.B {}\n", flags.join(", ")));
+        if let Some(about) = arg.get_about() {
+            roff.push_str(&format!("{}\n", about));
+        }
+        let possible_values = arg.get_possible_values().unwrap_or_default();
+        if !possible_values.is_empty() {
+            roff.push_str(&format!("[possible values: {}]\n", possible_values.join(", ")));
+        }
+    }
+
+    fn render_subcommand(roff: &mut String, sub: &App) {
+        roff.push_str(&format!(".TP\n.B {}\n", sub.get_name()));
+        if let Some(about) = sub.get_about() {
+            roff.push_str(&format!("{}\n", about));
+        }
+    }
+}
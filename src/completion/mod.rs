@@ -1,7 +1,7 @@
-use std::{ops::Not, path::PathBuf};
+use std::{io, ops::Not, path::PathBuf};
 
 use clap::{App, IntoApp};
-use clap_generate::{generate_to, Generator};
+use clap_generate::{generate, generate_to, Generator};
 #[cfg(unix)]
 use clap_generate::generators::{Bash, Elvish, Fish, Zsh};
 #[cfg(windows)]
@@ -11,7 +11,7 @@ use colored::Colorize;
 
 use crate::{
     cli::Cli,
-    completion::{app::ZrApp, model::Shell},
+    completion::{app::ZrApp, man::ManPage, model::Shell},
 };
 use crate::config::global::Config;
 use crate::template::InitializrTemplate;
@@ -22,6 +22,7 @@ use crate::utils::user::User;
 
 pub mod model;
 mod app;
+mod man;
 pub mod dynamic_app;
 
 pub(crate) type PreInitializrArgs = Vec<(PathBuf, Option<LocalInitializrArgs>)>;
@@ -32,19 +33,38 @@ pub struct CliCompletion {}
 impl CliCompletion {
     const ZSH_DIR: &'static str = "/usr/local/share/zsh/site-functions";
     const BASH_DIR: &'static str = ".bash_completion.d";
+    const FISH_DIR: &'static str = ".config/fish/completions";
+    const ELVISH_DIR: &'static str = ".config/elvish/lib";
+    const POWERSHELL_DIR: &'static str = ".config/powershell";
 
-    pub fn apply(desired_shell: Option<Shell>) {
+    pub fn apply(desired_shell: Option<Shell>, stdout: bool) {
         if let Some(shell) = desired_shell.or_else(Shell::current) {
             info!("Will generate completion files for {}", shell.as_ref().green());
-            let config = Config::get();
-            let initializr_args = Self::initializr_args(config.clone());
-            let app = Self::app(Cli::into_app(), &config, &initializr_args);
-            Self::create_completion_for(app, shell);
+            Self::generate_completions(shell, stdout);
         } else {
             warn!("Could not determine current shell. Consider passing it explicitly e.g. 'zr completion zsh'")
         }
     }
 
+    /// Generates a completion script for `shell` from the fully assembled app (zr's own
+    /// args plus every template's dynamic args), so template authors get completions for
+    /// their `NamedArg`-declared flags without hand-writing any script.
+    pub fn generate_completions(shell: Shell, stdout: bool) {
+        let config = Config::get();
+        let initializr_args = Self::initializr_args(config.clone());
+        let app = Self::app(Cli::into_app(), &config, &initializr_args);
+        Self::create_completion_for(app, shell, stdout);
+    }
+
+    /// Generates a roff man page from the same fully assembled app, walking every `Arg`
+    /// produced by `NamedArg::from` the way ripgrep builds its manpage from its arg table.
+    pub fn generate_man(stdout: bool) {
+        let config = Config::get();
+        let initializr_args = Self::initializr_args(config.clone());
+        let app = Self::app(Cli::into_app(), &config, &initializr_args);
+        ManPage::generate(app, stdout);
+    }
+
     pub fn app<'a>(app: App<'a>, config: &'a Config, initializr_args: &'a PreInitializrArgs) -> App<'a> {
         ZrApp::app(app, config, initializr_args)
     }
@@ -57,18 +77,22 @@ impl CliCompletion {
             .collect()
     }
 
-    fn create_completion_for(mut app: App, shell: Shell) {
+    fn create_completion_for(mut app: App, shell: Shell, stdout: bool) {
         match shell {
-            Shell::Bash => Self::create_completion::<Bash>(&mut app, shell),
-            Shell::Zsh => Self::create_completion::<Zsh>(&mut app, shell),
-            Shell::Elvish => Self::create_completion::<Elvish>(&mut app, shell),
-            Shell::Fish => Self::create_completion::<Fish>(&mut app, shell),
-            Shell::Powershell => Self::create_completion::<PowerShell>(&mut app, shell),
+            Shell::Bash => Self::create_completion::<Bash>(&mut app, shell, stdout),
+            Shell::Zsh => Self::create_completion::<Zsh>(&mut app, shell, stdout),
+            Shell::Elvish => Self::create_completion::<Elvish>(&mut app, shell, stdout),
+            Shell::Fish => Self::create_completion::<Fish>(&mut app, shell, stdout),
+            Shell::Powershell => Self::create_completion::<PowerShell>(&mut app, shell, stdout),
         }
     }
 
-    fn create_completion<G: Generator>(app: &mut App, shell: Shell) {
+    fn create_completion<G: Generator>(app: &mut App, shell: Shell, stdout: bool) {
         let bin_name = app.get_name().to_string();
+        if stdout {
+            generate::<G, _>(app, &bin_name, &mut io::stdout());
+            return;
+        }
         let dir = Self::completion_dir(shell);
         generate_to::<G, _, _>(app, &bin_name, &dir);
         info!("Generated completion files for {} in {}", shell.as_ref().green(), format!("{:?}", dir).as_str().green());
@@ -78,10 +102,12 @@ impl CliCompletion {
         let dir = match shell {
             Shell::Zsh => PathBuf::from(Self::ZSH_DIR),
             Shell::Bash => User::home().map(|it| it.join(Self::BASH_DIR)).fail("Could not find home dir"),
-            _ => panic!("Not supported yet"),
+            Shell::Fish => User::home().map(|it| it.join(Self::FISH_DIR)).fail("Could not find home dir"),
+            Shell::Elvish => User::home().map(|it| it.join(Self::ELVISH_DIR)).fail("Could not find home dir"),
+            Shell::Powershell => User::home().map(|it| it.join(Self::POWERSHELL_DIR)).fail("Could not find home dir"),
         };
         if dir.exists().not() {
-            dir.create_dir()
+            dir.create_dir_all()
                 .fail(format!("Failed creating completion scripts folder {:?}", dir));
         }
         dir
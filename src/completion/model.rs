@@ -1,4 +1,7 @@
 use std::{env, fmt::Display, ops::Not, path::PathBuf};
+use std::process::Command;
+use std::sync::mpsc;
+use std::time::Duration;
 
 use clap::Clap;
 use strum::AsRefStr;
@@ -20,14 +23,39 @@ pub enum Shell {
     Powershell,
 }
 
+impl std::str::FromStr for Shell {
+    type Err = String;
+
+    fn from_str(raw: &str) -> Result<Self, Self::Err> {
+        match raw.to_lowercase().as_str() {
+            "bash" => Ok(Self::Bash),
+            "zsh" => Ok(Self::Zsh),
+            "fish" => Ok(Self::Fish),
+            "elvish" => Ok(Self::Elvish),
+            "powershell" => Ok(Self::Powershell),
+            other => Err(format!("Unknown shell '{}'", other)),
+        }
+    }
+}
+
 impl Shell {
     /// is always set in a bash shell
     const IS_BASH: &'static str = "BASH";
     /// is always set in a zsh shell but might be built-in
     const IS_ZSH: &'static str = "UPDATE_ZSH_DAYS";
+    /// how many ancestors we are ready to climb before giving up
+    const MAX_ANCESTORS: u8 = 10;
+    /// how long we wait for a `--version` invocation before giving up on it
+    const VERSION_TIMEOUT: Duration = Duration::from_millis(300);
 
+    /// Detects the shell actually invoking `zr` by walking up the parent process chain
+    /// looking for a known shell binary name, falling back to the (less reliable) env
+    /// var heuristics when the process tree can't be inspected or none of its ancestors
+    /// is a recognized shell.
     pub fn current() -> Option<Self> {
-        Self::is_bash().or_else(Self::is_zsh)
+        Self::from_process_tree()
+            .or_else(Self::is_bash)
+            .or_else(Self::is_zsh)
     }
 
     fn is_bash() -> Option<Self> {
@@ -42,6 +70,71 @@ impl Shell {
             .map(|_| Self::Zsh)
     }
 
+    /// Matches a process binary name (as reported by `ps`/`/proc`) against a known shell,
+    /// stripping the leading `-` some shells use to mark themselves as a login shell.
+    fn from_binary_name(name: &str) -> Option<Self> {
+        match name.trim_start_matches('-') {
+            "bash" => Some(Self::Bash),
+            "zsh" => Some(Self::Zsh),
+            "fish" => Some(Self::Fish),
+            "elvish" => Some(Self::Elvish),
+            "pwsh" | "powershell" | "powershell.exe" => Some(Self::Powershell),
+            _ => None,
+        }
+    }
+
+    /// Walks up the parent process chain (via `ps`, available on every unix and on
+    /// Windows through WSL/cygwin `ps` shims) looking for the first ancestor whose
+    /// binary name matches a known shell.
+    fn from_process_tree() -> Option<Self> {
+        let mut pid = std::process::id().to_string();
+        for _ in 0..Self::MAX_ANCESTORS {
+            let (name, ppid) = Self::process_info(&pid)?;
+            if let Some(shell) = Self::from_binary_name(&name) {
+                return Some(shell);
+            }
+            if ppid == pid || ppid == "0" { break; }
+            pid = ppid;
+        }
+        None
+    }
+
+    /// Returns `(comm, ppid)` for the given pid by shelling out to `ps`, the only
+    /// common denominator across the unices `zr` targets.
+    fn process_info(pid: &str) -> Option<(String, String)> {
+        let output = Command::new("ps")
+            .args(&["-o", "comm=,ppid=", "-p", pid])
+            .output().ok()?;
+        let line = String::from_utf8(output.stdout).ok()?;
+        let mut parts = line.split_whitespace();
+        let name = parts.next()?.to_string();
+        let ppid = parts.next()?.to_string();
+        Some((name, ppid))
+    }
+
+    /// Runs `<shell> --version` with a short timeout and returns its first output line,
+    /// letting templates and diagnostics condition on the invoking shell's capabilities.
+    pub fn version(&self) -> Option<String> {
+        let binary = self.binary_name();
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let _ = tx.send(Command::new(binary).arg("--version").output());
+        });
+        rx.recv_timeout(Self::VERSION_TIMEOUT).ok()?.ok()
+            .and_then(|out| String::from_utf8(out.stdout).ok())
+            .and_then(|raw| raw.lines().next().map(str::to_string))
+    }
+
+    fn binary_name(&self) -> &'static str {
+        match self {
+            Self::Bash => "bash",
+            Self::Zsh => "zsh",
+            Self::Fish => "fish",
+            Self::Elvish => "elvish",
+            Self::Powershell => "pwsh",
+        }
+    }
+
     /// Executes the given cmd in current shell on MacOs or Linux
     #[cfg(unix)]
     pub fn run(cmd: &str, into: &PathBuf) -> anyhow::Result<(i32, String, String)> {
@@ -86,36 +179,61 @@ mod shell_tests {
     }
 
     #[test]
-    fn should_detect_bash_shell() {
+    fn should_detect_bash_shell_from_env_var_fallback() {
         before_all();
         env::set_var(Shell::IS_BASH, "/bin/bash");
-        assert_eq!(Shell::current(), Some(Shell::Bash));
+        assert_eq!(Shell::is_bash(), Some(Shell::Bash));
     }
 
     #[test]
     fn should_not_detect_bash_when_env_var_empty() {
         before_all();
         env::set_var(Shell::IS_BASH, "");
-        assert!(Shell::current().is_none());
+        assert!(Shell::is_bash().is_none());
     }
 
     #[test]
-    fn should_detect_zsh_shell() {
+    fn should_detect_zsh_shell_from_env_var_fallback() {
         before_all();
         env::set_var(Shell::IS_ZSH, "/bin/zsh");
-        assert_eq!(Shell::current(), Some(Shell::Zsh));
+        assert_eq!(Shell::is_zsh(), Some(Shell::Zsh));
     }
 
     #[test]
     fn should_not_detect_zsh_when_env_var_empty() {
         before_all();
         env::set_var(Shell::IS_ZSH, "");
-        assert!(Shell::current().is_none());
+        assert!(Shell::is_zsh().is_none());
     }
 
     #[test]
-    fn should_not_detect_any_when_no_env_var_present() {
+    fn should_not_detect_any_fallback_when_no_env_var_present() {
         before_all();
-        assert!(Shell::current().is_none());
+        assert!(Shell::is_bash().is_none());
+        assert!(Shell::is_zsh().is_none());
+    }
+
+    mod binary_name {
+        use super::*;
+
+        #[test]
+        fn should_recognize_every_known_shell() {
+            assert_eq!(Shell::from_binary_name("bash"), Some(Shell::Bash));
+            assert_eq!(Shell::from_binary_name("zsh"), Some(Shell::Zsh));
+            assert_eq!(Shell::from_binary_name("fish"), Some(Shell::Fish));
+            assert_eq!(Shell::from_binary_name("elvish"), Some(Shell::Elvish));
+            assert_eq!(Shell::from_binary_name("pwsh"), Some(Shell::Powershell));
+            assert_eq!(Shell::from_binary_name("powershell"), Some(Shell::Powershell));
+        }
+
+        #[test]
+        fn should_strip_login_shell_dash_prefix() {
+            assert_eq!(Shell::from_binary_name("-zsh"), Some(Shell::Zsh));
+        }
+
+        #[test]
+        fn should_not_recognize_unknown_binary() {
+            assert!(Shell::from_binary_name("sshd").is_none());
+        }
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,47 @@
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+/// A single alias expansion in `zr.toml`, mirroring how Cargo reads an aliased command:
+/// either a whitespace-separated line (`new = "create --from rust-lib"`) or an already
+/// tokenized list (`new = ["create", "--from", "rust-lib"]`), the latter useful once a
+/// token itself needs to contain whitespace.
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(untagged)]
+pub enum AliasCmd {
+    Line(String),
+    Tokens(Vec<String>),
+}
+
+impl AliasCmd {
+    /// Splits this alias into the tokens it expands to: whitespace-split for the string
+    /// form, verbatim for the list form.
+    pub fn tokens(&self) -> Vec<String> {
+        match self {
+            Self::Line(line) => line.split_whitespace().map(String::from).collect_vec(),
+            Self::Tokens(tokens) => tokens.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod alias_cmd_tests {
+    use super::*;
+
+    #[test]
+    fn should_tokenize_string_form() {
+        let alias = AliasCmd::Line(String::from("new rust app"));
+        assert_eq!(alias.tokens(), vec!["new", "rust", "app"]);
+    }
+
+    #[test]
+    fn should_keep_list_form_as_is() {
+        let alias = AliasCmd::Tokens(vec![String::from("create"), String::from("--from"), String::from("rust-lib")]);
+        assert_eq!(alias.tokens(), vec!["create", "--from", "rust-lib"]);
+    }
+
+    #[test]
+    fn should_tokenize_empty_list_form() {
+        let alias = AliasCmd::Tokens(vec![]);
+        assert!(alias.tokens().is_empty());
+    }
+}
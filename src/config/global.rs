@@ -1,11 +1,16 @@
+use std::collections::{BTreeMap, HashSet};
 use std::path::PathBuf;
 
+use clap::IntoApp;
 use colored::Colorize;
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
+use crate::cli::Cli;
+use crate::config::alias::AliasCmd;
 use crate::config::hash::ConfigHash;
 use crate::upgrade::upgradable::Upgradable;
+use crate::utils::anyhow_err::ErrConversion;
 use crate::utils::error::ErrorExt;
 use crate::utils::file::PathExt;
 use crate::utils::marshall::Tomlable;
@@ -21,6 +26,16 @@ cached! {
 pub struct Config {
     // pub repositories: Option<RepoConfig>,
     pub repositories: Option<Vec<String>>,
+    /// User-defined command aliases, expanded to their target invocation before clap parses argv
+    ///
+    /// e.g. `aliases = { rs = "new rust app" }` makes `zr rs my-project` equivalent to
+    /// `zr new rust app my-project`. Each entry can also be a list (`rs = ["new", "rust",
+    /// "app"]`) for the rare token that itself needs to contain whitespace, mirroring how
+    /// Cargo accepts either a string or a list for an aliased command.
+    pub aliases: Option<BTreeMap<String, AliasCmd>>,
+    /// Per-repository SSH private key path, keyed by the repository's URL as it
+    /// appears in `repositories`, tried before falling back to an ssh-agent identity
+    pub ssh_keys: Option<BTreeMap<String, String>>,
 }
 
 impl Config {
@@ -68,6 +83,86 @@ impl Config {
                     .collect_vec()
             }).unwrap_or_default()
     }
+
+    /// Persists this config back to `config.toml`
+    pub fn save(&self) -> anyhow::Result<()> {
+        confy::store(Zr::NAME, self.clone()).wrap()
+    }
+
+    /// Template names provided by repository `url`, if its local cache currently exists
+    pub fn templates_for(&self, url: &str) -> Vec<String> {
+        Self::find(url.config_hash())
+            .map(|dir| dir.children().into_iter().filter_map(|it| it.file_name_str().map(String::from)).collect_vec())
+            .unwrap_or_default()
+    }
+
+    /// Whether repository `url`'s local cache currently exists on disk
+    pub fn is_cached(url: &str) -> bool {
+        Self::find(url.config_hash()).is_some()
+    }
+
+    /// Explicit SSH key path configured for `url`, if any
+    pub fn ssh_key_for(&self, url: &str) -> Option<&str> {
+        self.ssh_keys.as_ref()
+            .and_then(|keys| keys.get(url))
+            .map(String::as_str)
+    }
+
+    /// All template names known across configured repositories, used to power
+    /// "did you mean '<x>'?" suggestions when a requested template is unknown.
+    pub fn template_names(&self) -> Vec<String> {
+        self.all_templates().iter()
+            .flat_map(|it| it.children())
+            .filter_map(|it| it.file_name_str().map(String::from))
+            .collect_vec()
+    }
+
+    /// Splices user-defined aliases in place of the first argument after the binary name,
+    /// tokenizing a string expansion on whitespace (a list expansion is used verbatim),
+    /// mirroring how cargo resolves an
+    /// `aliased_command` from config before dispatching.
+    ///
+    /// A builtin `SubCommand` name is never shadowed, and a token is never expanded twice
+    /// so an alias that (directly or transitively) refers to itself is left as-is instead
+    /// of recursing forever.
+    ///
+    /// Tokens typed by the user after the alias keyword are kept after the expansion rather
+    /// than dropped. Nothing de-duplicates them against the expansion: a single-value arg
+    /// given both by the alias and on the command line reaches clap twice, and clap rejects
+    /// it as used more than once rather than keeping the explicit one.
+    pub fn expand_alias(&self, args: Vec<String>) -> Vec<String> {
+        let aliases = match self.aliases.as_ref() {
+            Some(aliases) => aliases,
+            None => return args,
+        };
+        let mut args = args.into_iter();
+        let bin = match args.next() {
+            Some(bin) => bin,
+            None => return vec![],
+        };
+        let mut rest = args.collect_vec();
+        let builtins = Self::builtin_subcommand_names();
+        let mut already_expanded = HashSet::new();
+        while let Some(first) = rest.first().cloned() {
+            if builtins.contains(&first) || already_expanded.contains(&first) {
+                break;
+            }
+            let expansion = match aliases.get(first.as_str()) {
+                Some(expansion) => expansion,
+                None => break,
+            };
+            already_expanded.insert(first);
+            let expanded_tokens = expansion.tokens();
+            rest = expanded_tokens.into_iter().chain(rest.into_iter().skip(1)).collect();
+        }
+        std::iter::once(bin).chain(rest).collect()
+    }
+
+    fn builtin_subcommand_names() -> HashSet<String> {
+        Cli::into_app().get_subcommands()
+            .map(|it| it.get_name().to_string())
+            .collect()
+    }
 }
 
 impl Upgradable for Config {
@@ -163,5 +258,131 @@ pub mod config_tests {
             let config = Config::from(vec![UNKNOWN_REMOTE]);
             assert!(config.find_template("rust-app").is_none());
         }
+
+        #[test]
+        fn template_names_should_list_all_known_templates() {
+            before_all();
+            let config = Config::from(vec![DEFAULT_REMOTE]);
+            assert!(config.template_names().contains(&String::from("rust-app")));
+        }
+
+        #[test]
+        fn templates_for_should_list_templates_of_one_repository() {
+            before_all();
+            let config = Config::from(vec![DEFAULT_REMOTE]);
+            assert!(config.templates_for(DEFAULT_REMOTE).contains(&String::from("rust-app")));
+        }
+
+        #[test]
+        fn is_cached_should_find_locally_installed_repository() {
+            before_all();
+            assert!(Config::is_cached(DEFAULT_REMOTE));
+            assert!(Config::is_cached(UNKNOWN_REMOTE).not());
+        }
+    }
+
+    mod aliases_config_tests {
+        use std::collections::BTreeMap;
+        use std::iter::FromIterator;
+
+        use super::*;
+
+        fn strings(args: Vec<&str>) -> Vec<String> {
+            args.into_iter().map(String::from).collect_vec()
+        }
+
+        fn with_aliases(pairs: Vec<(&str, &str)>) -> Config {
+            let aliases = BTreeMap::from_iter(pairs.into_iter().map(|(k, v)| (k.to_string(), AliasCmd::Line(v.to_string()))));
+            Config { aliases: Some(aliases), ..Default::default() }
+        }
+
+        fn with_list_alias(name: &str, tokens: Vec<&str>) -> Config {
+            let aliases = BTreeMap::from_iter(vec![(name.to_string(), AliasCmd::Tokens(tokens.into_iter().map(String::from).collect_vec()))]);
+            Config { aliases: Some(aliases), ..Default::default() }
+        }
+
+        #[test]
+        fn should_expand_known_alias() {
+            let config = with_aliases(vec![("rs", "new rust app")]);
+            let expanded = config.expand_alias(strings(vec!["zr", "rs", "my-project", "--force"]));
+            assert_eq!(expanded, strings(vec!["zr", "new", "rust", "app", "my-project", "--force"]));
+        }
+
+        #[test]
+        fn should_leave_unknown_command_untouched() {
+            let config = with_aliases(vec![("rs", "new rust app")]);
+            let expanded = config.expand_alias(strings(vec!["zr", "new", "rust", "app", "my-project"]));
+            assert_eq!(expanded, strings(vec!["zr", "new", "rust", "app", "my-project"]));
+        }
+
+        #[test]
+        fn should_leave_args_untouched_when_no_aliases_configured() {
+            let config = Config::default();
+            let expanded = config.expand_alias(strings(vec!["zr", "rs", "my-project"]));
+            assert_eq!(expanded, strings(vec!["zr", "rs", "my-project"]));
+        }
+
+        #[test]
+        fn should_expand_transitively() {
+            let config = with_aliases(vec![("rs", "fastapi"), ("fastapi", "new --lang python --template fastapi-service")]);
+            let expanded = config.expand_alias(strings(vec!["zr", "rs", "my-project"]));
+            assert_eq!(
+                expanded,
+                strings(vec!["zr", "new", "--lang", "python", "--template", "fastapi-service", "my-project"])
+            );
+        }
+
+        #[test]
+        fn should_not_recurse_on_alias_cycle() {
+            let config = with_aliases(vec![("a", "b"), ("b", "a")]);
+            let expanded = config.expand_alias(strings(vec!["zr", "a", "my-project"]));
+            assert_eq!(expanded, strings(vec!["zr", "a", "my-project"]));
+        }
+
+        #[test]
+        fn should_never_shadow_a_builtin_subcommand() {
+            let config = with_aliases(vec![("new", "get-config")]);
+            let expanded = config.expand_alias(strings(vec!["zr", "new", "rust", "app", "my-project"]));
+            assert_eq!(expanded, strings(vec!["zr", "new", "rust", "app", "my-project"]));
+        }
+
+        #[test]
+        fn should_expand_list_form_alias() {
+            let config = with_list_alias("create", vec!["create", "--from", "rust-lib"]);
+            let expanded = config.expand_alias(strings(vec!["zr", "create", "my-project"]));
+            assert_eq!(expanded, strings(vec!["zr", "create", "--from", "rust-lib", "my-project"]));
+        }
+
+        #[test]
+        fn explicit_flag_should_come_after_alias_supplied_one() {
+            let config = with_aliases(vec![("rs", "new rust app --template basic")]);
+            let expanded = config.expand_alias(strings(vec!["zr", "rs", "my-project", "--template", "full"]));
+            assert_eq!(
+                expanded,
+                strings(vec!["zr", "new", "rust", "app", "--template", "basic", "my-project", "--template", "full"])
+            );
+        }
+    }
+
+    mod ssh_key_config_tests {
+        use std::collections::BTreeMap;
+        use std::iter::FromIterator;
+
+        use super::*;
+
+        #[test]
+        fn should_find_configured_key_for_url() {
+            let config = Config {
+                ssh_keys: Some(BTreeMap::from_iter(vec![(String::from("[email protected]:org/repo.git"), String::from("~/.ssh/id_repo"))])),
+                ..Default::default()
+            };
+            assert_eq!(config.ssh_key_for("[email protected]:org/repo.git"), Some("~/.ssh/id_repo"));
+        }
+
+        #[test]
+        fn should_find_no_key_for_unconfigured_url() {
+            let config = Config::default();
+            assert_eq!(config.ssh_key_for("[email protected]:org/repo.git"), None);
+        }
     }
 }
\ No newline at end of file
@@ -2,6 +2,7 @@ use std::io::{stdin, stdout, Write};
 
 use colored::Colorize;
 
+use crate::console::non_interactive::NonInteractive;
 use crate::utils::error::ErrorExt;
 
 /// Wraps utilities asking user for a choice
@@ -10,15 +11,65 @@ pub struct Asker {}
 impl Asker {
     const YES: &'static str = "y";
 
-    /// Asks a yes/no question.
-    /// Captures user answer from stdin.
+    /// Asks a yes/no question, pre-seeded answer sources taking precedence over stdin
+    /// (see [`NonInteractive`]).
     pub fn ask<F>(question: &str, if_yes: F) -> bool where F: Fn() {
+        let user_answered_yes = NonInteractive::confirm(question)
+            .unwrap_or_else(|| Self::prompt_yes_no(question));
+        if user_answered_yes { if_yes(); }
+        user_answered_yes
+    }
+
+    fn prompt_yes_no(question: &str) -> bool {
+        NonInteractive::ensure_tty_or_fail(question);
         let mut yes_no = String::new();
         print!("{} {}", question, "(Y/n): ".bold().yellow());
         let _ = stdout().flush();
         stdin().read_line(&mut yes_no).fail("Invalid answer");
-        let user_answered_yes = yes_no.trim().eq_ignore_ascii_case(Self::YES);
-        if user_answered_yes { if_yes(); }
-        user_answered_yes
+        yes_no.trim().eq_ignore_ascii_case(Self::YES)
+    }
+
+    /// Asks for a free-text value, pre-filling `default` as the answer given on an empty line,
+    /// pre-seeded answer sources taking precedence over stdin (see [`NonInteractive`]).
+    pub fn ask_value(question: &str, default: Option<&str>) -> String {
+        if let Some(answer) = NonInteractive::answer(question) {
+            return answer;
+        }
+        NonInteractive::ensure_tty_or_fail(question);
+        let mut answer = String::new();
+        match default {
+            Some(default) => print!("{} {}: ", question, format!("[{}]", default).dimmed()),
+            None => print!("{}: ", question),
+        }
+        let _ = stdout().flush();
+        stdin().read_line(&mut answer).fail("Invalid answer");
+        match answer.trim() {
+            "" => default.unwrap_or_default().to_string(),
+            trimmed => trimmed.to_string(),
+        }
+    }
+
+    /// Asks to pick one of `choices`, re-prompting until a valid choice number is given,
+    /// pre-seeded answer sources taking precedence over stdin (see [`NonInteractive`]).
+    pub fn ask_choice(question: &str, choices: &[String]) -> String {
+        if let Some(answer) = NonInteractive::answer(question).filter(|it| choices.contains(it)) {
+            return answer;
+        }
+        NonInteractive::ensure_tty_or_fail(question);
+        println!("{}", question.bold());
+        choices.iter().enumerate().for_each(|(i, choice)| println!("  {}) {}", i + 1, choice));
+        loop {
+            let mut answer = String::new();
+            print!("{} ", "choice:".yellow());
+            let _ = stdout().flush();
+            stdin().read_line(&mut answer).fail("Invalid answer");
+            let picked = answer.trim().parse::<usize>().ok()
+                .and_then(|it| it.checked_sub(1))
+                .and_then(|i| choices.get(i));
+            match picked {
+                Some(choice) => return choice.to_string(),
+                None => println!("{}", "Please enter a valid choice number".red()),
+            }
+        }
     }
 }
\ No newline at end of file
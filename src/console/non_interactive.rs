@@ -0,0 +1,88 @@
+use std::{collections::BTreeMap, env, path::PathBuf};
+
+use atty::Stream;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::error::ErrorExt;
+use crate::utils::marshall::Tomlable;
+
+/// Resolves an answer for a console prompt without ever touching stdin, so CI and
+/// scripted runs never block on a question nobody is there to answer.
+///
+/// Answers are looked up in precedence order: an explicit `--yes`/`--no` flag, the
+/// `ZR_ASSUME_YES` env var, then a response file (pointed to by `ZR_ANSWERS_FILE`)
+/// mapping question keys to answers. Checked ahead of clap parsing, same as
+/// [`crate::cmd::InitializrStdArgs::is_interactive`], since [`crate::console::asker::Asker`]
+/// is called from places that don't carry a parsed [`crate::cli::Cli`] around.
+pub struct NonInteractive;
+
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+struct Answers(BTreeMap<String, String>);
+
+impl Tomlable for Answers {}
+
+impl NonInteractive {
+    const YES_FLAGS: [&'static str; 1] = ["--yes"];
+    const NO_FLAGS: [&'static str; 1] = ["--no"];
+    const ASSUME_YES_VAR: &'static str = "ZR_ASSUME_YES";
+    const ANSWERS_FILE_VAR: &'static str = "ZR_ANSWERS_FILE";
+
+    /// Resolves a yes/no question ahead of ever prompting, or `None` when nothing
+    /// pre-seeds an answer and the interactive prompt should run instead.
+    pub fn confirm(question: &str) -> Option<bool> {
+        if env::args().any(|it| Self::YES_FLAGS.contains(&it.as_str())) { return Some(true); }
+        if env::args().any(|it| Self::NO_FLAGS.contains(&it.as_str())) { return Some(false); }
+        if env::var(Self::ASSUME_YES_VAR).is_ok() { return Some(true); }
+        Self::answers().get(question).map(|it| Self::is_truthy(it))
+    }
+
+    /// Resolves a free-text or choice answer from the response file, keyed by `question`.
+    pub fn answer(question: &str) -> Option<String> {
+        Self::answers().get(question).cloned()
+    }
+
+    /// Fails fast with a clear message instead of letting `stdin().read_line` hang forever.
+    pub fn ensure_tty_or_fail(question: &str) {
+        if !atty::is(Stream::Stdin) {
+            None::<()>.fail(format!(
+                "Cannot prompt '{}': no TTY attached and no answer configured \
+                (pass --yes/--no, set {}, or point {} at a response file)",
+                question, Self::ASSUME_YES_VAR, Self::ANSWERS_FILE_VAR,
+            ));
+        }
+    }
+
+    fn is_truthy(answer: &str) -> bool {
+        matches!(answer.to_ascii_lowercase().as_str(), "y" | "yes" | "true")
+    }
+
+    fn answers() -> BTreeMap<String, String> {
+        env::var(Self::ANSWERS_FILE_VAR).ok()
+            .map(PathBuf::from)
+            .filter(|it| it.exists())
+            .map(Answers::from_file_or_fail)
+            .map(|it| it.0)
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod non_interactive_tests {
+    use super::*;
+
+    #[test]
+    fn should_recognize_truthy_answers() {
+        assert!(NonInteractive::is_truthy("y"));
+        assert!(NonInteractive::is_truthy("Yes"));
+        assert!(NonInteractive::is_truthy("true"));
+        assert!(!NonInteractive::is_truthy("n"));
+        assert!(!NonInteractive::is_truthy(""));
+    }
+
+    #[test]
+    fn should_have_no_answers_when_env_var_unset() {
+        env::remove_var(NonInteractive::ANSWERS_FILE_VAR);
+        assert!(NonInteractive::answers().is_empty());
+        assert_eq!(NonInteractive::answer("project-name"), None);
+    }
+}
@@ -1,6 +1,23 @@
-#[derive(Default, Debug, Clone, Eq, PartialEq, PartialOrd, Ord)]
+use crate::template::predicate::ArgPredicate;
+
+#[derive(Default, Debug, Clone, PartialEq)]
 pub struct ArgCmd {
     pub order: u8,
     pub cmd: String,
     pub is_default: bool,
-}
\ No newline at end of file
+    pub predicate: Option<ArgPredicate>,
+}
+
+impl Eq for ArgCmd {}
+
+impl PartialOrd for ArgCmd {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ArgCmd {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.order, &self.cmd, self.is_default).cmp(&(other.order, &other.cmd, other.is_default))
+    }
+}
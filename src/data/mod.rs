@@ -9,11 +9,15 @@ use crate::{
     completion::{CliCompletion, PreInitializrArgs},
 };
 use crate::cli::Cli;
+use crate::console::asker::Asker;
+use crate::data::arg_cmd::ArgCmd;
 use crate::data::data_arg::DataArg;
+use crate::template::arg_type::ArgType;
 use crate::template::local_arg::LocalInitializrArg;
+use crate::utils::error::ErrorExt;
 
 use super::{
-    cmd::{InitializrStdArgs, InitializrStdArgsFieldName},
+    cmd::{InitializrStdArgs, InitializrStdArgsFieldName, VcsKind},
 };
 use crate::config::global::Config;
 
@@ -46,10 +50,46 @@ impl InitializrData {
     fn parse_project_name(matched_args: Option<&ArgMatches>) -> DataArg {
         let proj_name = matched_args
             .and_then(|it| it.value_of(InitializrStdArgs::PROJECT_NAME_ARG_NAME))
+            .map(String::from)
+            .or_else(|| InitializrStdArgs::is_interactive().then(|| Asker::ask_value("Project name", None)))
             .map(Value::from);
         DataArg::from((String::from(Self::PROJ_KEY), proj_name, None))
     }
 
+    /// Prompts for every local template arg that wasn't supplied, when `--interactive` was passed
+    fn interactive_answers<'a>(matched_args: Option<&'a ArgMatches>, local_args: &'a PreInitializrArgs) -> Vec<DataArg> {
+        if !InitializrStdArgs::is_interactive() {
+            return vec![];
+        }
+        let matched_args = match matched_args {
+            Some(it) => it,
+            None => return vec![],
+        };
+        Self::local_template_args(local_args)
+            .filter_map(|(key, maybe_arg)| maybe_arg.map(|arg| (key, arg)))
+            .filter(|(_, arg)| arg.maybe_cmd().is_none())
+            .filter(|(key, _)| matched_args.occurrences_of(key) == 0)
+            .filter_map(|(key, arg)| {
+                let value = Self::ask_for(key, &arg)?;
+                let coerced = Self::coerce(key, &Some(arg.clone()), value);
+                Some(DataArg::from((Self::trim_arg_key(key), Some(coerced), Some(arg))))
+            })
+            .collect_vec()
+    }
+
+    /// Prompts for a single missing arg's value: a yes/no question for flags (answering 'no'
+    /// yields no entry, matching flag-absent semantics), a select list for enum-typed args,
+    /// and a free-text prompt pre-filled with the declared default otherwise
+    fn ask_for(key: &str, arg: &LocalInitializrArg) -> Option<Value> {
+        if arg.maybe_flag().is_some() {
+            return if Asker::ask(&format!("Enable '{}'?", key), || {}) { Some(Value::from(true)) } else { None };
+        }
+        match arg.value_type.as_ref() {
+            Some(ArgType::Enum { values }) => Some(Value::from(Asker::ask_choice(key, values))),
+            _ => Some(Value::from(Asker::ask_value(key, arg.default_value().as_deref()))),
+        }
+    }
+
     fn parse_args<'a>(matched_args: Option<&'a ArgMatches>, local_args: &'a PreInitializrArgs) -> impl Iterator<Item=DataArg> + 'a {
         Self::local_template_args(&local_args)
             .merge_by(InitializrStdArgs::variants().into_iter().map(|it| (it, None)), |_, _| true)
@@ -68,10 +108,76 @@ impl InitializrData {
                                 .map(Value::from)
                         } else { a.value_of(key).map(Value::from) }
                     })
+                    .map(|value| value.map(|v| Self::coerce(key, &maybe_arg, v)))
                     .map(|value| DataArg::from((Self::trim_arg_key(key), value, maybe_arg)))
             })
     }
 
+    /// Validates/coerces a raw matched value (or array of values, for a multi-arg)
+    /// into the JSON shape declared by the arg's `type`, failing with the arg's name
+    /// and clap's `possible_values`-style context on mismatch
+    fn coerce(key: &str, maybe_arg: &Option<LocalInitializrArg>, value: Value) -> Value {
+        maybe_arg.as_ref()
+            .map(|arg| arg.coerce(value.clone()).fail(format!("Invalid value for arg '{}'", key)))
+            .unwrap_or(value)
+    }
+
+    /// Fails if a present, subcommand-promoted arg `requires` an absent one, or
+    /// `conflicts_with` one that's also present. Only needed for args with `subcommands`
+    /// set: those are rendered as a nested `App` (see `NamedSubcommand::from`), never as a
+    /// plain `Arg`, so they don't pick up the `requires_all`/`conflicts_with_all` that
+    /// `NamedArg::from` already attaches for every ordinary arg
+    fn enforce_predicates(matched_args: Option<&ArgMatches>, local_args: &PreInitializrArgs) {
+        let matched_args = match matched_args {
+            Some(it) => it,
+            None => return,
+        };
+        for (key, maybe_arg) in Self::local_template_args(local_args) {
+            let arg = match maybe_arg {
+                Some(it) if it.subcommands.is_some() => it,
+                _ => continue,
+            };
+            if !matched_args.is_present(key) {
+                continue;
+            }
+            for required in arg.requires.iter().flatten() {
+                Some(()).filter(|_| matched_args.is_present(required))
+                    .fail(format!("'--{}' requires '--{}' to also be set", key, required));
+            }
+            for conflicting in arg.conflicts_with.iter().flatten() {
+                Some(()).filter(|_| !matched_args.is_present(conflicting))
+                    .fail(format!("'--{}' conflicts with '--{}', only one of them can be set", key, conflicting));
+            }
+        }
+    }
+
+    /// Injects a `DataArg` holding the conditional default for every arg whose
+    /// `default_value_if` trigger is present and which wasn't explicitly supplied;
+    /// `parse_args` yields no entry at all for an absent, non-default arg, so this
+    /// only ever adds entries, it never overwrites one
+    fn conditional_defaults<'a>(matched_args: Option<&'a ArgMatches>, local_args: &'a PreInitializrArgs) -> Vec<DataArg> {
+        let matched_args = match matched_args {
+            Some(it) => it,
+            None => return vec![],
+        };
+        Self::local_template_args(local_args)
+            .filter_map(|(key, maybe_arg)| maybe_arg.map(|arg| (key, arg)))
+            .filter(|(key, _)| !matched_args.is_present(key))
+            .filter_map(|(key, arg)| {
+                let default_value_if = arg.default_value_if.as_ref()?;
+                let predicate = &default_value_if.predicate;
+                let triggered = matched_args.is_present(&predicate.arg) && predicate.value.as_ref()
+                    .map(|expected| matched_args.value_of(&predicate.arg) == Some(expected.as_str()))
+                    .unwrap_or(true);
+                if !triggered {
+                    return None;
+                }
+                let value = Self::coerce(key, &Some(arg.clone()), Value::from(default_value_if.default.as_str()));
+                Some(DataArg::from((Self::trim_arg_key(key), Some(value), Some(arg))))
+            })
+            .collect_vec()
+    }
+
     fn trim_arg_key(key: &str) -> String {
         key.trim_start_matches(|c: char| c == '-').to_string()
     }
@@ -97,13 +203,19 @@ impl From<PreInitializrArgs> for InitializrData {
         let new_matches = dyn_app.get_matches();
         let matched_args = Self::matched_args(&new_matches);
         let proj = Self::parse_project_name(matched_args);
+        // Plain args' requires/conflicts_with are already enforced by clap itself (see
+        // `NamedArg`'s `requires_all`/`conflicts_with_all`) during `get_matches()` above;
+        // `enforce_predicates` only re-checks the subcommand-promoted ones clap doesn't cover
+        Self::enforce_predicates(matched_args, &local_args);
+        let conditional_defaults = Self::conditional_defaults(matched_args, &local_args);
+        let interactive_answers = Self::interactive_answers(matched_args, &local_args);
         let (args, commands): (Vec<DataArg>, Vec<DataArg>) = Self::parse_args(matched_args, &local_args)
             .merge_by(vec![proj].into_iter(), |_, _| true)
+            .merge_by(conditional_defaults.into_iter(), |_, _| true)
+            .merge_by(interactive_answers.into_iter(), |_, _| true)
             .partition(|it| it.is_not_cmd());
-        let cmds = commands.iter()
-            .filter_map(|it| it.cmd.to_owned())
-            .sorted()
-            .map(|it| it.cmd)
+        let cmds = commands.into_iter()
+            .filter_map(|it| it.cmd)
             .collect_vec();
         Self::from((
             args.into_iter().collect_vec(),
@@ -112,12 +224,61 @@ impl From<PreInitializrArgs> for InitializrData {
     }
 }
 
-impl From<(Vec<DataArg>, Vec<String>)> for InitializrData {
-    fn from((args, commands): (Vec<DataArg>, Vec<String>)) -> Self {
-        Self {
-            args: args.into_iter().flat_map(|it| it.variants).collect(),
-            commands,
-        }
+impl From<(Vec<DataArg>, Vec<ArgCmd>)> for InitializrData {
+    fn from((args, commands): (Vec<DataArg>, Vec<ArgCmd>)) -> Self {
+        let args: Map<String, Value> = args.into_iter().flat_map(|it| it.variants).collect();
+        // Gating on the resolved args map (rather than `ArgMatches`) lets a command's
+        // predicate see coerced values and injected defaults, not just raw presence
+        let commands = commands.into_iter()
+            .filter(|it| it.predicate.as_ref().map(|p| p.matches(&args)).unwrap_or(true))
+            .sorted()
+            .map(|it| it.cmd)
+            .collect_vec();
+        Self { args, commands }
+    }
+}
+
+#[cfg(test)]
+mod cmd_predicate_tests {
+    use crate::template::predicate::ArgPredicate;
+
+    use super::*;
+
+    fn cmd(cmd: &str, predicate: Option<ArgPredicate>) -> ArgCmd {
+        ArgCmd { order: 0, cmd: cmd.to_string(), is_default: false, predicate }
+    }
+
+    fn data_arg(key: &str, value: &str) -> DataArg {
+        DataArg { variants: vec![(key.to_string(), Value::from(value))], cmd: None }
+    }
+
+    #[test]
+    fn should_keep_cmd_without_predicate() {
+        let data = InitializrData::from((vec![], vec![cmd("ls .", None)]));
+        assert_eq!(data.commands, vec![String::from("ls .")]);
+    }
+
+    #[test]
+    fn should_keep_cmd_when_predicate_matches_resolved_value() {
+        let predicate = ArgPredicate { arg: String::from("db"), value: Some(String::from("postgres")) };
+        let args = vec![data_arg("db", "postgres")];
+        let data = InitializrData::from((args, vec![cmd("cargo add sqlx --features postgres", Some(predicate))]));
+        assert_eq!(data.commands, vec![String::from("cargo add sqlx --features postgres")]);
+    }
+
+    #[test]
+    fn should_drop_cmd_when_predicate_does_not_match_resolved_value() {
+        let predicate = ArgPredicate { arg: String::from("db"), value: Some(String::from("postgres")) };
+        let args = vec![data_arg("db", "mysql")];
+        let data = InitializrData::from((args, vec![cmd("cargo add sqlx --features postgres", Some(predicate))]));
+        assert!(data.commands.is_empty());
+    }
+
+    #[test]
+    fn should_drop_cmd_when_predicate_arg_is_absent() {
+        let predicate = ArgPredicate { arg: String::from("db"), value: None };
+        let data = InitializrData::from((vec![], vec![cmd("cargo add sqlx", Some(predicate))]));
+        assert!(data.commands.is_empty());
     }
 }
 
@@ -134,6 +295,20 @@ impl InitializrData {
     pub fn is_vs_code(&self) -> bool { self.contains(InitializrStdArgsFieldName::Code.name()) }
     pub fn should_render_readme(&self) -> bool { self.contains(InitializrStdArgsFieldName::Readme.name()) }
     pub fn is_dry(&self) -> bool { self.contains(InitializrStdArgsFieldName::Dry.name()) }
+    pub fn should_show_context(&self) -> bool { self.contains(InitializrStdArgsFieldName::ShowContext.name()) }
+    pub fn is_verify(&self) -> bool { self.contains(InitializrStdArgsFieldName::Verify.name()) }
+    /// The `--container <image>` value, when template commands should run in a container
+    /// instead of on the host
+    pub fn container_image(&self) -> Option<&str> {
+        self.args.get(InitializrStdArgsFieldName::Container.name()).and_then(Value::as_str)
+    }
+    /// The resolved `--vcs` backend to initialize in the generated project, defaulting to git
+    pub fn vcs(&self) -> VcsKind {
+        self.args.get(InitializrStdArgsFieldName::Vcs.name())
+            .and_then(Value::as_str)
+            .and_then(|it| it.parse().ok())
+            .unwrap_or_default()
+    }
     fn contains(&self, key: &str) -> bool {
         self.args.iter().any(|(k, _)| k == key)
     }
@@ -1,23 +1,52 @@
-use clap::Clap;
+use clap::{Clap, ErrorKind, IntoApp};
+use colored::Colorize;
 
-use crate::{cli::{Cli, SubCommand::*}, cli_log::CliLog, completion::CliCompletion, get_config::GetConfig, Initializr, upgrade::ZrUpgrade};
+use crate::{cli::{Cli, RepoCommand, SubCommand::*}, cli_log::CliLog, completion::CliCompletion, config::global::Config, get_config::GetConfig, Initializr, upgrade::ZrUpgrade};
+use crate::repo::RepoActions;
+use crate::utils::levenshtein;
 
 /// Executable entrypoint
 pub struct CliEntryPoint;
 
 impl CliEntryPoint {
     pub fn run() {
-        let cli = Cli::parse();
-        CliLog::init(&cli.log);
+        let args = Config::get().expand_alias(std::env::args().collect());
+        let cli = Cli::try_parse_from(&args).unwrap_or_else(|err| Self::suggest_subcommand_then_exit(&args, err));
+        CliLog::init(&cli.log, &cli.color);
+        if let Some(shell) = cli.generate_completions {
+            return CliCompletion::generate_completions(shell, true);
+        }
+        if cli.generate_man {
+            return CliCompletion::generate_man(true);
+        }
         if let Some(cmd) = cli.cmd {
             match cmd {
                 Upgrade {} => ZrUpgrade::upgrade(),
-                Completion { shell } => { CliCompletion::apply(shell); }
+                Completion { shell, stdout } => { CliCompletion::apply(shell, stdout); }
                 New { lang } => Initializr::bootstrap(lang),
                 GetConfig => GetConfig::exec(),
+                Repo { action } => match action {
+                    RepoCommand::Add { url } => RepoActions::add(url),
+                    RepoCommand::Remove { url } => RepoActions::remove(url),
+                    RepoCommand::List => RepoActions::list(),
+                },
             }
         } else {
             panic!("Not implemented yet !");
         }
     }
+
+    /// On an unrecognized top-level subcommand, appends a "did you mean '<x>'?" hint
+    /// before exiting the way clap's own error display otherwise would.
+    fn suggest_subcommand_then_exit(args: &[String], err: clap::Error) -> ! {
+        if err.kind == ErrorKind::UnrecognizedSubcommand {
+            if let Some(unknown) = args.get(1) {
+                let builtins = Cli::into_app().get_subcommands().map(|it| it.get_name().to_string()).collect::<Vec<_>>();
+                if let Some(suggestion) = levenshtein::closest_match(unknown, builtins.iter().map(String::as_str)) {
+                    eprintln!("{} did you mean '{}'?", "error:".red().bold(), suggestion.yellow());
+                }
+            }
+        }
+        err.exit()
+    }
 }
\ No newline at end of file
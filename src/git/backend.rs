@@ -0,0 +1,160 @@
+use std::path::PathBuf;
+use std::process::Command;
+
+use crate::console::command::CommandExt;
+use crate::utils::anyhow_err::{ErrConversion, OptConversion};
+use crate::utils::file::PathExt;
+
+use super::Git;
+
+/// A DVCS used to fetch and update template sources.
+///
+/// [`GitBackend`] is the default, fully-featured implementation backed by [`Git`]; third
+/// parties can implement this trait for other version control systems and plug it into
+/// [`resolve`] so templates can be hosted outside of git.
+pub trait Backend {
+    /// Clones `uri` into `from_dir/into_dir`, returning the resulting path
+    fn clone(&self, from_dir: &PathBuf, uri: &str, into_dir: &str, branch: Option<&str>, with_history: bool, ssh_key: Option<&str>) -> Option<PathBuf>;
+
+    /// Fast-forwards an existing clone at `path`
+    fn pull_rebase(&self, path: &PathBuf) -> anyhow::Result<()>;
+
+    /// Initializes a fresh repository at `path`
+    fn init(&self, path: &PathBuf) -> anyhow::Result<()>;
+
+    /// Stages `file` for the next commit in the repository rooted at `path`
+    fn add(&self, path: &PathBuf, file: &PathBuf);
+}
+
+/// Default backend, delegating to the existing [`Git`] wrapper
+pub struct GitBackend;
+
+impl Backend for GitBackend {
+    fn clone(&self, from_dir: &PathBuf, uri: &str, into_dir: &str, branch: Option<&str>, with_history: bool, ssh_key: Option<&str>) -> Option<PathBuf> {
+        Git::clone(from_dir, uri, into_dir, branch, with_history, ssh_key)
+    }
+
+    fn pull_rebase(&self, path: &PathBuf) -> anyhow::Result<()> {
+        Git::pull_rebase(path)
+    }
+
+    fn init(&self, path: &PathBuf) -> anyhow::Result<()> {
+        Git::init(path).map(|_| ())
+    }
+
+    fn add(&self, path: &PathBuf, file: &PathBuf) {
+        Git::from(path).add(file)
+    }
+}
+
+/// Backend identified by an `hg+` URI prefix; shells out to the `hg` binary since this repo
+/// does not depend on a Mercurial crate
+pub struct HgBackend;
+
+impl Backend for HgBackend {
+    fn clone(&self, from_dir: &PathBuf, uri: &str, into_dir: &str, branch: Option<&str>, _with_history: bool, _ssh_key: Option<&str>) -> Option<PathBuf> {
+        let mut args = vec!["clone", uri, into_dir];
+        if let Some(branch) = branch {
+            args.extend(&["-b", branch]);
+        }
+        cmd("hg", &args, from_dir).map(|_| from_dir.join(into_dir))
+    }
+
+    fn pull_rebase(&self, path: &PathBuf) -> anyhow::Result<()> {
+        cmd("hg", &["pull", "--rebase"], path)
+            .wrap(format!("'hg pull --rebase' failed in {:?}", path))
+    }
+
+    fn init(&self, path: &PathBuf) -> anyhow::Result<()> {
+        cmd("hg", &["init"], path)
+            .wrap(format!("'hg init' failed in {:?}", path))
+    }
+
+    fn add(&self, path: &PathBuf, file: &PathBuf) {
+        cmd("hg", &["add", file.path_str()], path);
+    }
+}
+
+/// Backend identified by a `fossil+` URI prefix; shells out to the `fossil` binary since this
+/// repo does not depend on a Fossil crate
+pub struct FossilBackend;
+
+impl Backend for FossilBackend {
+    fn clone(&self, from_dir: &PathBuf, uri: &str, into_dir: &str, _branch: Option<&str>, _with_history: bool, _ssh_key: Option<&str>) -> Option<PathBuf> {
+        let into = from_dir.join(into_dir);
+        into.create_dir().ok()?;
+        let clone_file = into.join(".fossil");
+        cmd("fossil", &["clone", uri, clone_file.path_str()], &into)
+            .and_then(|_| cmd("fossil", &["open", ".fossil"], &into))
+            .map(|_| into)
+    }
+
+    fn pull_rebase(&self, path: &PathBuf) -> anyhow::Result<()> {
+        cmd("fossil", &["update"], path)
+            .wrap(format!("'fossil update' failed in {:?}", path))
+    }
+
+    fn init(&self, path: &PathBuf) -> anyhow::Result<()> {
+        cmd("fossil", &["init", ".fossil"], path)
+            .wrap(format!("'fossil init' failed in {:?}", path))
+    }
+
+    fn add(&self, path: &PathBuf, file: &PathBuf) {
+        cmd("fossil", &["add", file.path_str()], path);
+    }
+}
+
+fn cmd(bin: &str, args: &[&str], from_dir: &PathBuf) -> Option<()> {
+    Command::new(bin)
+        .current_dir(from_dir)
+        .args(args)
+        .no_output()
+        .spawn_and_wait()
+        .map(|_| ())
+        .ok()
+}
+
+/// Resolves the [`Backend`] to use for a configured template repository URL from its
+/// `<scheme>+` prefix (`git+…`, `hg+…`, `fossil+…`), along with the URL stripped of that
+/// prefix. A bare URL without a recognized prefix defaults to [`GitBackend`], preserving the
+/// pre-existing behaviour of treating every repository as a git remote.
+pub fn resolve(uri: &str) -> (Box<dyn Backend>, &str) {
+    if let Some(rest) = uri.strip_prefix("git+") {
+        (Box::new(GitBackend), rest)
+    } else if let Some(rest) = uri.strip_prefix("hg+") {
+        (Box::new(HgBackend), rest)
+    } else if let Some(rest) = uri.strip_prefix("fossil+") {
+        (Box::new(FossilBackend), rest)
+    } else {
+        (Box::new(GitBackend), uri)
+    }
+}
+
+#[cfg(test)]
+mod backend_resolve_tests {
+    use super::*;
+
+    #[test]
+    fn should_strip_git_prefix() {
+        let (_, uri) = resolve("git+https://github.com/beltram/my-zr-config.git");
+        assert_eq!(uri, "https://github.com/beltram/my-zr-config.git");
+    }
+
+    #[test]
+    fn should_strip_hg_prefix() {
+        let (_, uri) = resolve("hg+https://hg.example.org/my-zr-config");
+        assert_eq!(uri, "https://hg.example.org/my-zr-config");
+    }
+
+    #[test]
+    fn should_strip_fossil_prefix() {
+        let (_, uri) = resolve("fossil+https://fossil.example.org/my-zr-config");
+        assert_eq!(uri, "https://fossil.example.org/my-zr-config");
+    }
+
+    #[test]
+    fn should_default_bare_url_to_git() {
+        let (_, uri) = resolve("https://github.com/beltram/my-zr-config.git");
+        assert_eq!(uri, "https://github.com/beltram/my-zr-config.git");
+    }
+}
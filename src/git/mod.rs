@@ -1,12 +1,14 @@
-use std::{path::PathBuf, process::Command, str};
+use std::{env, path::PathBuf, process::Command, str};
 
-use git2::Repository;
+use git2::{build::RepoBuilder, Cred, CredentialType, FetchOptions, RemoteCallbacks, Repository, SubmoduleUpdateOptions};
 
 use crate::console::command::CommandExt;
 use crate::utils::anyhow_err::{ErrConversion, OptConversion};
 use crate::utils::error::ErrorExt;
 use crate::utils::file::PathExt;
 
+pub mod backend;
+
 /// Git commands wrapper
 #[derive(new)]
 pub struct Git { pub repo: Repository }
@@ -22,19 +24,87 @@ impl From<Repository> for Git {
     fn from(repo: Repository) -> Self { Self { repo } }
 }
 
+/// The authentication scheme implied by a template config's remote URL, used to give
+/// more actionable feedback when a clone fails.
+#[derive(Debug, Eq, PartialEq)]
+pub enum GitRemote {
+    Https,
+    Ssh,
+}
+
+impl GitRemote {
+    pub fn is_ssh(&self) -> bool { matches!(self, Self::Ssh) }
+
+    /// scp-like syntax e.g. `git@github.com:beltram/my-zr-config.git`
+    fn is_scp_like(uri: &str) -> bool {
+        !uri.contains("://") && uri.contains('@') && uri.contains(':')
+    }
+}
+
+impl<'a> From<&'a str> for GitRemote {
+    fn from(uri: &'a str) -> Self {
+        if uri.starts_with("ssh://") || Self::is_scp_like(uri) {
+            Self::Ssh
+        } else {
+            Self::Https
+        }
+    }
+}
+
 impl Git {
     /// Clones a remote git repository
     /// * `from_dir` - Performs git clone into this directory
     /// * `uri` - Remote git uri to clone
     /// * `into_dir` - Name of the created folder
     /// * `branch` - Branch to checkout after clone
-    pub fn clone(from_dir: &PathBuf, uri: &str, into_dir: &str, branch: Option<&str>, with_history: bool) -> Option<PathBuf> {
+    /// * `ssh_key` - Explicit private key path to try for SSH remotes, before an ssh-agent identity
+    pub fn clone(from_dir: &PathBuf, uri: &str, into_dir: &str, branch: Option<&str>, with_history: bool, ssh_key: Option<&str>) -> Option<PathBuf> {
         let into_dir = &from_dir.join(into_dir);
-        Self::clone_cmd(from_dir, uri, branch, with_history, into_dir)
+        Self::clone_git2(uri, into_dir, branch, with_history, ssh_key)
+            .or_else(|| Self::clone_cmd(from_dir, uri, branch, with_history, into_dir))
+            .map(|repository| {
+                Self::init_submodules(&repository, with_history);
+                repository
+            })
             .map(|repository| Self::root_path(&Self::new(repository)))
     }
 
-    /// Clones with external git command if credentials not found locally
+    /// Clones via git2, authenticating through [`Self::credentials_callback`]
+    fn clone_git2(uri: &str, into_dir: &PathBuf, branch: Option<&str>, with_history: bool, ssh_key: Option<&str>) -> Option<Repository> {
+        let mut callbacks = RemoteCallbacks::new();
+        callbacks.credentials(Self::credentials_callback(ssh_key.map(PathBuf::from)));
+        let mut fetch_opts = FetchOptions::new();
+        fetch_opts.remote_callbacks(callbacks);
+        if !with_history { fetch_opts.depth(1); }
+        let mut builder = RepoBuilder::new();
+        builder.fetch_options(fetch_opts);
+        if let Some(branch) = branch { builder.branch(branch); }
+        builder.clone(uri, into_dir).ok()
+    }
+
+    /// Tries, in order: an explicit per-repository key path, an ssh-agent identity,
+    /// then HTTPS username/token from the environment
+    fn credentials_callback(ssh_key: Option<PathBuf>) -> impl FnMut(&str, Option<&str>, CredentialType) -> Result<Cred, git2::Error> {
+        move |_url, username_from_url, allowed_types| {
+            let username = username_from_url.unwrap_or("git");
+            if allowed_types.contains(CredentialType::SSH_KEY) {
+                if let Some(cred) = ssh_key.as_ref().and_then(|key| Cred::ssh_key(username, None, key, None).ok()) {
+                    return Ok(cred);
+                }
+                if let Ok(cred) = Cred::ssh_key_from_agent(username) {
+                    return Ok(cred);
+                }
+            }
+            if allowed_types.contains(CredentialType::USER_PASS_PLAINTEXT) {
+                if let (Ok(user), Ok(token)) = (env::var("ZR_GIT_USERNAME"), env::var("ZR_GIT_TOKEN")) {
+                    return Cred::userpass_plaintext(&user, &token);
+                }
+            }
+            Cred::default()
+        }
+    }
+
+    /// Falls back to the external git command when git2 couldn't authenticate
     fn clone_cmd(from_dir: &PathBuf, uri: &str, branch: Option<&str>, with_history: bool, into_dir: &PathBuf) -> Option<Repository> {
         let dir_name = into_dir.path_str();
         if with_history {
@@ -47,9 +117,47 @@ impl Git {
             .and_then(|_| Repository::open(into_dir).ok())
     }
 
+    /// Recursively initializes submodules so templates sharing partials (license headers,
+    /// CI fragments, ...) through them come up populated rather than empty.
+    ///
+    /// Tries git2 first, falling back to the external git command, mirroring the
+    /// [`Self::clone`]/[`Self::add`] fallback. A shallow clone (`with_history == false`)
+    /// shallows the submodule checkout too, since a full submodule history defeats the point
+    /// of `--depth 1` on the parent.
+    fn init_submodules(repo: &Repository, with_history: bool) {
+        if Self::update_submodules_git2(repo, with_history).is_none() {
+            if let Some(root) = repo.workdir() {
+                let root = root.to_path_buf();
+                let mut args = vec!["submodule", "update", "--init", "--recursive"];
+                if !with_history { args.extend(&["--depth", "1"]); }
+                Self::cmd(&args, &root);
+            }
+        }
+    }
+
+    /// Walks submodules depth-first via git2, bailing out with `None` on the first failure
+    /// so [`Self::init_submodules`] can fall back to the external git command
+    fn update_submodules_git2(repo: &Repository, with_history: bool) -> Option<()> {
+        for mut submodule in repo.submodules().ok()? {
+            let mut fetch_opts = FetchOptions::new();
+            if !with_history { fetch_opts.depth(1); }
+            let mut update_opts = SubmoduleUpdateOptions::new();
+            update_opts.fetch(fetch_opts);
+            submodule.update(true, Some(&mut update_opts)).ok()?;
+            if let Ok(sub_repo) = submodule.open() {
+                Self::update_submodules_git2(&sub_repo, with_history)?;
+            }
+        }
+        Some(())
+    }
+
     pub fn pull_rebase(from: &PathBuf) -> anyhow::Result<()> {
         Self::cmd(&["pull", "--rebase"], from)
-            .wrap(format!("'git pull --rebase' failed in {:?}", from))
+            .wrap(format!("'git pull --rebase' failed in {:?}", from))?;
+        if let Ok(repo) = Repository::open(from) {
+            Self::init_submodules(&repo, true);
+        }
+        Ok(())
     }
 
     /// 'git init'
@@ -81,3 +189,28 @@ impl Git {
             .to_path_buf()
     }
 }
+
+#[cfg(test)]
+mod git_remote_tests {
+    use super::*;
+
+    #[test]
+    fn should_detect_https() {
+        assert_eq!(GitRemote::from("https://github.com/beltram/my-zr-config.git"), GitRemote::Https);
+    }
+
+    #[test]
+    fn should_detect_ssh_url() {
+        assert_eq!(GitRemote::from("ssh://git@github.com/beltram/my-zr-config.git"), GitRemote::Ssh);
+    }
+
+    #[test]
+    fn should_detect_scp_like_syntax() {
+        assert_eq!(GitRemote::from("git@github.com:beltram/my-zr-config.git"), GitRemote::Ssh);
+    }
+
+    #[test]
+    fn should_not_mistake_windows_path_for_scp_like() {
+        assert_eq!(GitRemote::from("C:\\Users\\beltram\\my-zr-config"), GitRemote::Https);
+    }
+}
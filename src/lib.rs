@@ -14,7 +14,8 @@ extern crate serde;
 #[macro_use]
 extern crate shells;
 
-use std::{env, path::PathBuf};
+use std::{env, path::{Path, PathBuf}};
+use std::collections::HashMap;
 use std::iter::FromIterator;
 
 use colored::Colorize;
@@ -33,8 +34,12 @@ use crate::{
 use crate::config::global::Config;
 use crate::console::asker::Asker;
 use crate::data::InitializrData;
+use crate::template::front_matter::FrontMatter;
+use crate::template::zrignore::ZrIgnore;
+use crate::utils::cfg_expr;
 use crate::utils::error::ErrorExt;
 use crate::utils::file::PathExt;
+use crate::utils::levenshtein;
 
 use self::{actions::ProjectActions};
 use self::handlebars::Context;
@@ -53,6 +58,7 @@ pub mod cli_log;
 pub mod entrypoint;
 pub mod get_config;
 pub mod upgrade;
+pub mod repo;
 #[cfg(test)]
 pub mod mocks;
 
@@ -64,35 +70,189 @@ impl Initializr {
     const HIDDEN_PATH_ESCAPER: char = '!';
 
     pub fn bootstrap(lang: InitializrLang) {
-        let template_path = Self::find_template(Config::get(), lang.clone())
-            .fail(format!("No template found for lang '{}' and kind '{}'", lang.lang.as_str().yellow(), lang.kind.kind.as_str().yellow()));
+        let config = Config::get();
+        let template_path = Self::find_template(config.clone(), lang.clone())
+            .fail(Self::unknown_template_msg(&config, &lang));
         let initializr_flags = CliCompletion::initializr_args(Config::get()).into_iter()
             .filter(|(path, _)| path.parent() == Some(template_path.as_path()))
             .collect_vec();
         Self::new_project(template_path, InitializrData::from(initializr_flags));
     }
 
+    /// Prints the resolved `InitializrData` as pretty JSON instead of generating the project
+    fn show_context(data: &InitializrData) {
+        let json = serde_json::to_string_pretty(data).wrap()
+            .fail("Failed serializing resolved context to JSON");
+        println!("{}", json);
+    }
+
+    fn unknown_template_msg(config: &Config, lang: &InitializrLang) -> String {
+        let name = format!("{}-{}", lang.lang, lang.kind.kind);
+        let base = format!("No template found for lang '{}' and kind '{}'", lang.lang.as_str().yellow(), lang.kind.kind.as_str().yellow());
+        let known = config.template_names();
+        match levenshtein::closest_match(&name, known.iter().map(String::as_str)) {
+            Some(suggestion) => format!("{}, did you mean '{}'?", base, suggestion.yellow()),
+            None => base,
+        }
+    }
+
     /// Bootstraps a new project based on flags provided
     fn new_project(template_path: PathBuf, data: InitializrData) {
+        if data.should_show_context() {
+            return Self::show_context(&data);
+        }
+        if data.is_verify() {
+            return Self::verify_project(template_path, data);
+        }
         info!("Generating project {} from {}", data.project_name().green(), template_path.path_str().green());
         let mut handlebar: Handlebars = Handlebars::new();
         handlebar.register_templates_directory(Self::TEMPLATE_EXTENSION, &template_path)
             .fail(format!("No templates found in {:?}", template_path));
+        let front_matter = Self::load_front_matter(&mut handlebar, &template_path, &data);
         let destination = env::current_dir().unexpected_failure().join(data.project_name());
         if Self::propose_overwrite(&destination, &data).is_ok() {
-            handlebar.get_templates().iter()
-                .map(|(name, _)| name)
-                .filter(|name| !name.ends_with(Self::README) || data.should_render_readme())
-                .flat_map(|name| Self::maybe_duplicate(&handlebar, name, &data))
-                .for_each(|(name, is_duplicate)| Self::render_template(&handlebar, data.clone(), name.as_str(), &destination, is_duplicate));
+            Self::candidate_files(&handlebar, &template_path, &data, &front_matter)
+                .for_each(|(name, is_duplicate, to)| Self::render_template(&handlebar, data.clone(), name.as_str(), &destination, is_duplicate, to.as_deref()));
             info!("Generated project in {}", format!("{:?}", destination).as_str().green());
+            // Rendering above runs entirely while still elevated, so nothing to chown yet;
+            // `then_initialize` itself chowns before `then_execute_commands` can drop
+            // privileges (see `ProjectActions::apply`)
             Self::then_initialize(destination, data);
         }
     }
 
-    fn render_template<D>(handlebar: &Handlebars, data: D, template_name: &str, destination: &PathBuf, is_duplicate: bool) where D: Serialize {
-        handlebar.render_template(template_name, &data).wrap()
-            .map(|name| destination.join(&name))
+    /// Strips the `---`-fenced front-matter header (if any) off every registered template's
+    /// raw source, re-registering its stripped body under the same name so rendering never
+    /// sees the header, and returns the resolved [`FrontMatter`] per file name for
+    /// `candidate_files` and `render_template` to act on.
+    fn load_front_matter(handlebar: &mut Handlebars, template_path: &PathBuf, data: &InitializrData) -> HashMap<String, FrontMatter> {
+        handlebar.get_templates().keys().cloned().collect_vec().into_iter()
+            .filter_map(|name| {
+                let source = template_path.join(format!("{}{}", name, Self::TEMPLATE_EXTENSION)).read_pretty();
+                let (body, header) = FrontMatter::split(&source);
+                let header = header?;
+                handlebar.register_template_string(&name, body)
+                    .fail(format!("Failed re-registering template '{}' after stripping its front-matter", name));
+                Some((name.clone(), FrontMatter::resolve(&header, handlebar, data)))
+            })
+            .collect()
+    }
+
+    /// Every (name, is_duplicate, destination-override) triple `new_project` would render,
+    /// after applying the README, front-matter `when`, `.cfg()` marker and `.zrignore`
+    /// filters, and expanding multi-arg duplicates
+    fn candidate_files<'a>(handlebar: &'a Handlebars, template_path: &PathBuf, data: &'a InitializrData, front_matter: &'a HashMap<String, FrontMatter>) -> impl Iterator<Item=(String, bool, Option<String>)> + 'a {
+        let zrignore = ZrIgnore::load(template_path);
+        handlebar.get_templates().iter()
+            .map(|(name, _)| name)
+            .filter(|name| !name.ends_with(Self::README) || data.should_render_readme())
+            .filter(|name| Self::matches_cfg(name, data))
+            .filter(move |name| !zrignore.is_ignored(name))
+            .filter(move |name| front_matter.get(*name).map(FrontMatter::should_render).unwrap_or(true))
+            .flat_map(move |name| {
+                let to = front_matter.get(name).and_then(|it| it.to.clone());
+                Self::maybe_duplicate(handlebar, name, data).into_iter()
+                    .map(move |(name, is_duplicate)| (name, is_duplicate, to.clone()))
+            })
+    }
+
+    /// Renders every template into a throwaway directory and compares it byte-for-byte
+    /// against the already-generated project at `<cwd>/<project-name>`, without touching
+    /// either tree. Prints a diff summary and exits non-zero when drift is found, so CI can
+    /// catch a project that diverged from the template it was generated from.
+    fn verify_project(template_path: PathBuf, data: InitializrData) {
+        let destination = env::current_dir().unexpected_failure().join(data.project_name());
+        if !destination.exists() {
+            eprintln!("{} {} does not exist, nothing to verify", emoji::FAILURE, format!("{:?}", destination).red());
+            std::process::exit(1);
+        }
+        let mut handlebar: Handlebars = Handlebars::new();
+        handlebar.register_templates_directory(Self::TEMPLATE_EXTENSION, &template_path)
+            .fail(format!("No templates found in {:?}", template_path));
+        let front_matter = Self::load_front_matter(&mut handlebar, &template_path, &data);
+        let rendered = env::temp_dir().join(format!("zr-verify-{}-{}", std::process::id(), data.project_name()));
+        rendered.create_dir_all_or_fail();
+        Self::candidate_files(&handlebar, &template_path, &data, &front_matter)
+            .for_each(|(name, is_duplicate, to)| Self::render_template(&handlebar, data.clone(), name.as_str(), &rendered, is_duplicate, to.as_deref()));
+        let report = Self::diff_rendered(&rendered, &destination);
+        rendered.delete_dir().else_warn(format!("Failed cleaning up temporary {:?}", rendered));
+        if report.is_empty() {
+            info!("{} {} matches template {}", emoji::PARTY, format!("{:?}", destination).green(), template_path.path_str().green());
+        } else {
+            report.iter().for_each(|line| println!("{}", line));
+            eprintln!("{} {} drifted file(s) found between {} and template {}",
+                emoji::FAILURE, report.len(), format!("{:?}", destination).red(), template_path.path_str().red());
+            std::process::exit(1);
+        }
+    }
+
+    /// One line per missing, extra or byte-different file between `rendered` and `existing`
+    fn diff_rendered(rendered: &PathBuf, existing: &PathBuf) -> Vec<String> {
+        let rendered_files = Self::relative_files(rendered);
+        let existing_files = Self::relative_files(existing);
+        let mut report = rendered_files.difference(&existing_files)
+            .map(|it| format!("- missing: {:?}", it))
+            .collect_vec();
+        report.extend(existing_files.difference(&rendered_files).map(|it| format!("+ extra: {:?}", it)));
+        report.extend(rendered_files.intersection(&existing_files)
+            .filter(|rel| rendered.join(rel).read_binary() != existing.join(rel).read_binary())
+            .map(|rel| format!("~ differs: {:?}", rel)));
+        report
+    }
+
+    fn relative_files(root: &PathBuf) -> std::collections::BTreeSet<PathBuf> {
+        root.walk_glob("*").into_iter()
+            .filter_map(|it| it.strip_prefix(root).ok().map(Path::to_path_buf))
+            .collect()
+    }
+
+    /// Whether a template file's `.cfg(<expr>)` marker (if any) matches the current args +
+    /// host platform. A file without a marker always matches; one with a malformed
+    /// expression is warned about and dropped, mirroring [`crate::template::local_arg::LocalInitializrArg::matches_platform`].
+    fn matches_cfg(name: &str, data: &InitializrData) -> bool {
+        if !cfg_expr::has_cfg_marker(name) {
+            return true;
+        }
+        cfg_expr::extract_marker(name).1
+            .else_warn(format!("Invalid cfg() marker in template file '{}', dropping it", name))
+            .map(|expr| expr.eval(&cfg_expr::active_for_data(&data.args)))
+            .unwrap_or(false)
+    }
+
+    /// Resolves a rendered file name against `destination`, the way `destination.join(name)`
+    /// would, except it never lets `name` escape `destination`. A front-matter `to` is
+    /// Handlebars-rendered, untrusted text from the template itself (possibly fetched from an
+    /// arbitrary remote repo, see `crate::repo`), so an absolute `name` (which `PathBuf::join`
+    /// would otherwise honor verbatim, discarding `destination` entirely) or a relative one
+    /// whose `..` components walk back past `destination`'s root is rejected instead of
+    /// written anywhere on disk.
+    fn safe_join(destination: &PathBuf, name: &str) -> anyhow::Result<PathBuf> {
+        let mut relative = PathBuf::new();
+        for component in Path::new(name).components() {
+            match component {
+                std::path::Component::Normal(part) => relative.push(part),
+                std::path::Component::CurDir => {}
+                std::path::Component::ParentDir if relative.pop() => {}
+                _ => return Err(anyhow::Error::msg(format!("Rendered destination '{}' escapes project root, skipping it", name))),
+            }
+        }
+        Ok(destination.join(relative))
+    }
+
+    /// Renders `template_name` into `destination`, joined with either the rendered,
+    /// cfg-marker-stripped file name or, when the file carried a front-matter `to`, that
+    /// override rendered on its own instead. `template_name` itself goes through
+    /// `handlebar.render_template` before being joined, so a file path like
+    /// `{{package}}/{{projectName}}.rs.hbs` gets both its path segments and its contents
+    /// substituted from `data` — this is the repo's one scaffolding engine, there's no
+    /// separate raw-copy templating step (`PathExt::copy_all` stays a plain, non-templated
+    /// copy used only by test fixtures)
+    fn render_template<D>(handlebar: &Handlebars, data: D, template_name: &str, destination: &PathBuf, is_duplicate: bool, to: Option<&str>) where D: Serialize {
+        match to {
+            Some(to) => handlebar.render_template(to, &data).wrap(),
+            None => handlebar.render_template(template_name, &data).wrap().map(|name| cfg_expr::extract_marker(&name).0),
+        }
+            .and_then(|name| Self::safe_join(destination, &name))
             .and_then(|destination_file| {
                 destination_file.parent()
                     .wrap(format!("Could not find parent of {:?}", destination_file))
@@ -106,7 +266,7 @@ impl Initializr {
                             _ => Ok(destination_file.clone()),
                         }
                     })
-                    .map(Self::escape_hidden_files)
+                    .map(|destination_file| Self::escape_hidden_files(destination, destination_file))
             })
             .warn(format!("Handlebar failed rendering file '{}'", template_name.yellow()));
     }
@@ -138,15 +298,61 @@ impl Initializr {
         if duplicated.is_empty() { vec![(name.to_string(), false)] } else { duplicated }
     }
 
-    /// Hidden files are not supported by Handlebars. It is embarrassing for files or folder starting
-    /// with dot e.g. `.gitlab-ci.yml`, `.github`, `.gitignore`.
-    /// To overcome this those files have to be prefixed with '!' in templates.
-    /// Then, here, after write, we simply rename them.
-    fn escape_hidden_files(into_path: PathBuf) {
-        if let Some(filename) = into_path.file_name_str() {
-            filename.strip_prefix(Self::HIDDEN_PATH_ESCAPER)
-                .map(|it| into_path.with_file_name(it))
-                .map(|it| into_path.rename(&it));
+    /// Hidden files/directories aren't supported by Handlebars (a `.`-prefixed name confuses
+    /// its template loader), so template authors escape them with a leading `!`: `!gitignore`
+    /// renders as `.gitignore`. The escaper works over the whole relative path rather than
+    /// just the final component, so a dot-directory only needs escaping once at its top,
+    /// e.g. `!github/workflows/dev.yml` → `.github/workflows/dev.yml`, instead of repeating
+    /// `!` on every descendant. Only the portion of `into_path` below `destination` is ever
+    /// inspected or rewritten, so an unrelated `!`-prefixed ancestor of `destination` itself
+    /// (an odd but legal directory name on the host) can't cause every file in the project
+    /// to be un-escaped against it. After the file is written, un-escapes every `!`-prefixed
+    /// path component, moves the file to the real path, then prunes whatever now-empty
+    /// directories it leaves behind under a `!`-prefixed one, that one included.
+    fn escape_hidden_files(destination: &PathBuf, into_path: PathBuf) {
+        let relative = match into_path.strip_prefix(destination) {
+            Ok(relative) => relative,
+            Err(_) => return,
+        };
+        let is_escaped = relative.iter()
+            .any(|it| it.to_str().map(|s| s.starts_with(Self::HIDDEN_PATH_ESCAPER)).unwrap_or(false));
+        if !is_escaped {
+            return;
+        }
+        let unescaped_relative = relative.iter()
+            .map(|it| it.to_str().unwrap_or_default())
+            .map(|it| it.strip_prefix(Self::HIDDEN_PATH_ESCAPER).unwrap_or(it))
+            .collect::<PathBuf>();
+        let unescaped = destination.join(unescaped_relative);
+        unescaped.parent()
+            .wrap(format!("Could not find parent of {:?}", unescaped))
+            .and_then(|it| it.create_dir_all())
+            .and_then(|_| into_path.rename(&unescaped))
+            .warn(format!("Failed un-escaping hidden path {:?}", into_path));
+        if let Some(parent) = into_path.parent() {
+            Self::prune_escaped_dirs(destination, parent.to_path_buf());
+        }
+    }
+
+    /// Removes `dir` and walks up removing each ancestor above it, stopping at the first
+    /// directory that isn't empty (other escaped siblings still pending a move), whose path
+    /// below `destination` has no `!`-prefixed component, or that reaches back up to
+    /// `destination` itself
+    fn prune_escaped_dirs(destination: &PathBuf, mut dir: PathBuf) {
+        loop {
+            if dir == *destination {
+                break;
+            }
+            let is_escaped = dir.strip_prefix(destination).ok()
+                .map(|relative| relative.iter()
+                    .any(|it| it.to_str().map(|s| s.starts_with(Self::HIDDEN_PATH_ESCAPER)).unwrap_or(false)))
+                .unwrap_or(false);
+            if !is_escaped || std::fs::remove_dir(&dir).is_err() {
+                break;
+            }
+            if !dir.pop() {
+                break;
+            }
         }
     }
 
@@ -402,6 +608,52 @@ mod initializr_tests {
             assert!(gitignore_file.exists());
         }
 
+        #[test]
+        fn should_support_escaping_a_dot_directory_once_at_its_top() {
+            before_all();
+            let data = data("github-once-proj", &[]);
+            let project = new(data, "github-escape-once");
+            assert!(project.join(".github/workflows/dev.yml").exists());
+            assert!(project.join("!github").exists().not());
+        }
+
+        #[test]
+        fn should_only_render_files_whose_cfg_marker_matches() {
+            before_all();
+            let data = data("cfg-gated-proj", &[("feature", "cli")]);
+            let project = new(data, "cfg-gated");
+            assert!(project.join("cli.txt").exists());
+            assert!(project.join("other.txt").exists().not());
+        }
+
+        #[test]
+        fn should_skip_files_gated_by_front_matter_when() {
+            before_all();
+            let data = data("front-matter-proj", &[("feature", "cli")]);
+            let project = new(data, "front-matter");
+            assert!(project.join("cli.txt").exists());
+            assert!(project.join("other.txt").exists().not());
+        }
+
+        #[test]
+        fn should_override_destination_path_via_front_matter_to() {
+            before_all();
+            let data = data("front-matter-to-proj", &[]);
+            let project = new(data, "front-matter-to");
+            assert!(project.join("renamed.txt").exists());
+            assert!(project.join("source.txt").exists().not());
+        }
+
+        #[test]
+        fn should_skip_files_matched_by_zrignore() {
+            before_all();
+            let data = data("zrignore-proj", &[]);
+            let project = new(data, "zrignore-gated");
+            assert!(project.join("kept.txt").exists());
+            assert!(project.join("build/out.log").exists().not());
+            assert!(project.join("debug.log").exists().not());
+        }
+
         #[test]
         fn should_delete_generated_project_after_created_when_dry() {
             before_all();
@@ -422,6 +674,44 @@ mod initializr_tests {
         }
     }
 
+    mod verify {
+        use crate::mocks::MockFs;
+        use crate::utils::file::PathExt;
+        use crate::Initializr;
+
+        fn tree(root: &PathBuf, files: &[(&str, &str)]) {
+            root.create_dir_all_or_fail();
+            for (name, content) in files {
+                let file = root.join(name);
+                file.parent().unwrap().to_path_buf().create_dir_all_or_fail();
+                file.create().unwrap();
+                file.write_to(content);
+            }
+        }
+
+        #[test]
+        fn should_report_no_drift_for_identical_trees() {
+            let rendered = MockFs::home().join("verify-identical-rendered");
+            let existing = MockFs::home().join("verify-identical-existing");
+            tree(&rendered, &[("main.rs", "fn main() {}")]);
+            tree(&existing, &[("main.rs", "fn main() {}")]);
+            assert!(Initializr::diff_rendered(&rendered, &existing).is_empty());
+        }
+
+        #[test]
+        fn should_report_missing_extra_and_differing_files() {
+            let rendered = MockFs::home().join("verify-drifted-rendered");
+            let existing = MockFs::home().join("verify-drifted-existing");
+            tree(&rendered, &[("kept.rs", "same"), ("changed.rs", "new"), ("missing.rs", "")]);
+            tree(&existing, &[("kept.rs", "same"), ("changed.rs", "old"), ("extra.rs", "")]);
+            let report = Initializr::diff_rendered(&rendered, &existing);
+            assert!(report.iter().any(|it| it.starts_with('-') && it.contains("missing.rs")));
+            assert!(report.iter().any(|it| it.starts_with('+') && it.contains("extra.rs")));
+            assert!(report.iter().any(|it| it.starts_with('~') && it.contains("changed.rs")));
+            assert_eq!(report.len(), 3);
+        }
+    }
+
     fn data(name: &str, args: &[(&str, &str)]) -> InitializrData {
         let proj_key = String::from(InitializrData::PROJ_KEY);
         let proj = DataArg::from((proj_key, Some(Value::from(name)), None));
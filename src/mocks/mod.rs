@@ -10,6 +10,7 @@ use crate::utils::user::User;
 
 pub mod data;
 pub mod cmd;
+pub mod snapshot;
 
 lazy_static! {
     static ref TEMP_DIR: PathBuf = tempdir().unwrap().into_path();
@@ -34,7 +35,7 @@ impl<'a> MockFs {
     #[cfg(target_os = "windows")]
     const CONFIG_PATH: &'static str = "AppData/Roaming/zr/zr.toml";
 
-    const INITIALIZR_INSTALL_DIR: &'static str = "a875e39e74a89420d95c67576a5969e7fd4007cf296c0d267d624b4582f5ac8e";
+    pub(crate) const INITIALIZR_INSTALL_DIR: &'static str = "a875e39e74a89420d95c67576a5969e7fd4007cf296c0d267d624b4582f5ac8e";
 
     pub fn new() -> &'static Self { &MOCK_HOME }
 
@@ -0,0 +1,180 @@
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+use std::{env, fmt::Debug};
+
+use itertools::Itertools;
+
+use crate::utils::file::PathExt;
+
+use super::{data::Data, MockFs};
+
+/// Compares a freshly scaffolded project tree against a recorded golden directory under
+/// `tests/data/golden/<name>`, so a template regression shows up as a failing assertion
+/// instead of silently drifting. Borrows Cargo's own test-support approach: volatile
+/// substrings are normalized to stable placeholders before comparing, and a golden line
+/// may contain `[..]` to match any run of characters at that position.
+pub trait SnapshotExt where Self: AsRef<Path> + Debug {
+    /// Set to skip comparing and instead (re)write the golden directory from what was
+    /// actually generated.
+    const UPDATE_ENV: &'static str = "UPDATE_SNAPSHOTS";
+    const WILDCARD: &'static str = "[..]";
+
+    /// Asserts the tree rooted at `self` matches the golden directory `tests/data/golden/<name>`.
+    /// With `UPDATE_SNAPSHOTS=1` set, overwrites the golden directory instead of failing.
+    fn assert_tree_matches(&self, name: &str) {
+        let golden = Self::golden_dir(name);
+        if env::var(Self::UPDATE_ENV).is_ok() {
+            return Self::update_golden(self.as_ref(), &golden);
+        }
+        let report = Self::diff(self.as_ref(), &golden);
+        if !report.is_empty() {
+            panic!("Snapshot '{}' does not match {:?}:\n{}", name, golden, report.join("\n"));
+        }
+    }
+
+    fn golden_dir(name: &str) -> PathBuf {
+        Data::new("golden").path().join(name)
+    }
+
+    fn update_golden(generated: &Path, golden: &Path) {
+        if golden.exists() { golden.to_path_buf().delete_dir().ok(); }
+        golden.to_path_buf().create_dir_all_or_fail();
+        generated.to_path_buf().copy_all(&golden.to_path_buf());
+    }
+
+    /// One line per added file, missing file, or content mismatch; empty when the trees match.
+    fn diff(generated: &Path, golden: &Path) -> Vec<String> {
+        let generated_files = Self::relative_files(generated);
+        let golden_files = Self::relative_files(golden);
+        let mut report = golden_files.difference(&generated_files)
+            .map(|it| format!("- missing file: {:?}", it))
+            .collect_vec();
+        report.extend(generated_files.difference(&golden_files).map(|it| format!("+ unexpected file: {:?}", it)));
+        report.extend(generated_files.intersection(&golden_files).filter_map(|rel| Self::diff_file(generated, golden, rel)));
+        report
+    }
+
+    fn diff_file(generated: &Path, golden: &Path, rel: &PathBuf) -> Option<String> {
+        let actual = Self::normalize(generated.join(rel).read_to_string());
+        let expected = golden.join(rel).read_to_string();
+        let (actual_lines, expected_lines) = (actual.lines().collect_vec(), expected.lines().collect_vec());
+        let matches = actual_lines.len() == expected_lines.len()
+            && actual_lines.iter().zip(expected_lines.iter()).all(|(a, e)| Self::line_matches(e, a));
+        if matches {
+            None
+        } else {
+            Some(format!("~ {:?} differs:\n  expected: {:#?}\n  actual:   {:#?}", rel, expected_lines, actual_lines))
+        }
+    }
+
+    /// Substitutes every volatile substring (temp `home`, `CARGO_MANIFEST_DIR`, the
+    /// `INITIALIZR_INSTALL_DIR` hash) with a stable `[PLACEHOLDER]`.
+    fn normalize(content: String) -> String {
+        content
+            .replace(MockFs::home().path_str(), "[HOME]")
+            .replace(Data::env(), "[MANIFEST_DIR]")
+            .replace(MockFs::INITIALIZR_INSTALL_DIR, "[INSTALL_DIR]")
+    }
+
+    /// A golden line matches an actual line verbatim, or with any number of `[..]`
+    /// wildcards each matching a run of characters between (or around) the surrounding
+    /// literal text.
+    fn line_matches(pattern: &str, actual: &str) -> bool {
+        if !pattern.contains(Self::WILDCARD) {
+            return pattern == actual;
+        }
+        let parts = pattern.split(Self::WILDCARD).collect_vec();
+        let ends_with_wildcard = pattern.ends_with(Self::WILDCARD);
+        let mut rest = actual;
+        for (i, part) in parts.iter().enumerate() {
+            let is_last = i == parts.len() - 1;
+            if i == 0 {
+                match rest.strip_prefix(part.as_str()) {
+                    Some(stripped) => rest = stripped,
+                    None => return false,
+                }
+            } else if is_last && !ends_with_wildcard {
+                if !rest.ends_with(part.as_str()) { return false; }
+            } else {
+                match rest.find(part.as_str()) {
+                    Some(idx) => rest = &rest[idx + part.len()..],
+                    None => return false,
+                }
+            }
+        }
+        true
+    }
+
+    /// Every file path under `root`, relative to it, or an empty set when `root` doesn't exist
+    fn relative_files(root: &Path) -> BTreeSet<PathBuf> {
+        if !root.exists() {
+            return BTreeSet::new();
+        }
+        root.walk_glob("*").into_iter()
+            .filter_map(|it| it.strip_prefix(root).ok().map(Path::to_path_buf))
+            .collect()
+    }
+}
+
+impl SnapshotExt for PathBuf {}
+
+#[cfg(test)]
+mod snapshot_tests {
+    use crate::mocks::MockFs;
+
+    use super::*;
+
+    fn tree(root: &PathBuf, files: &[(&str, &str)]) {
+        root.create_dir_all_or_fail();
+        for (name, content) in files {
+            let file = root.join(name);
+            file.parent().unwrap().to_path_buf().create_dir_all_or_fail();
+            file.create().unwrap();
+            file.write_to(content);
+        }
+    }
+
+    #[test]
+    fn should_match_identical_trees() {
+        let a = MockFs::home().join("snapshot-identical-a");
+        let b = MockFs::home().join("snapshot-identical-b");
+        tree(&a, &[("main.rs", "fn main() {}")]);
+        tree(&b, &[("main.rs", "fn main() {}")]);
+        assert!(PathBuf::diff(&a, &b).is_empty());
+    }
+
+    #[test]
+    fn should_report_missing_and_unexpected_files() {
+        let a = MockFs::home().join("snapshot-mismatch-a");
+        let b = MockFs::home().join("snapshot-mismatch-b");
+        tree(&a, &[("only-in-a.rs", "")]);
+        tree(&b, &[("only-in-b.rs", "")]);
+        let diff = PathBuf::diff(&a, &b);
+        assert!(diff.iter().any(|it| it.contains("only-in-a.rs") && it.starts_with('+')));
+        assert!(diff.iter().any(|it| it.contains("only-in-b.rs") && it.starts_with('-')));
+    }
+
+    #[test]
+    fn should_report_content_mismatch() {
+        let a = MockFs::home().join("snapshot-content-a");
+        let b = MockFs::home().join("snapshot-content-b");
+        tree(&a, &[("lib.rs", "pub struct A;")]);
+        tree(&b, &[("lib.rs", "pub struct B;")]);
+        let diff = PathBuf::diff(&a, &b);
+        assert_eq!(diff.len(), 1);
+        assert!(diff[0].starts_with('~'));
+    }
+
+    #[test]
+    fn should_match_line_with_wildcard() {
+        assert!(PathBuf::line_matches("Generated in [..] seconds", "Generated in 1.2 seconds"));
+        assert!(PathBuf::line_matches("[..]/target/debug/zr", "/home/bob/target/debug/zr"));
+        assert!(!PathBuf::line_matches("Generated in [..] seconds", "Failed after 1.2 seconds"));
+    }
+
+    #[test]
+    fn should_normalize_volatile_substrings() {
+        let normalized = PathBuf::normalize(format!("home is {}", MockFs::home().path_str()));
+        assert_eq!(normalized, "home is [HOME]");
+    }
+}
@@ -0,0 +1,99 @@
+use colored::Colorize;
+use itertools::Itertools;
+
+use crate::config::global::Config;
+use crate::git::backend::resolve;
+use crate::git::GitRemote;
+use crate::upgrade::upgradable::Upgradable;
+use crate::utils::error::ErrorExt;
+
+/// `zr repo add/remove/list` — manages remote template repositories without
+/// hand-editing `config.toml`
+pub struct RepoActions {}
+
+impl RepoActions {
+    pub fn add(url: String) {
+        if !Self::is_valid_url(&url) {
+            return warn!("'{}' does not look like a valid git url, ignoring", url);
+        }
+        let mut config = Config::get();
+        let repositories = config.repositories.get_or_insert_with(Vec::new);
+        if repositories.contains(&url) {
+            return info!("'{}' is already registered", url.as_str().yellow());
+        }
+        repositories.push(url.clone());
+        config.save().fail("Failed saving zr config file");
+        Config::upgrade(config);
+        info!("Added repository {}", url.as_str().green());
+    }
+
+    pub fn remove(url: String) {
+        let mut config = Config::get();
+        match config.repositories.as_mut() {
+            Some(repositories) if repositories.contains(&url) => {
+                repositories.retain(|it| it != &url);
+                config.save().fail("Failed saving zr config file");
+                info!("Removed repository {}", url.as_str().green());
+            }
+            _ => warn!("'{}' is not a registered repository", url),
+        }
+    }
+
+    pub fn list() {
+        let config = Config::get();
+        match config.repositories.as_ref().filter(|it| !it.is_empty()) {
+            Some(repositories) => {
+                repositories.iter().for_each(|url| {
+                    let templates = config.templates_for(url).join(", ");
+                    let cached = if Config::is_cached(url) { "cached".green() } else { "not cached".yellow() };
+                    println!("{} ({}) - templates: [{}]", url, cached, templates);
+                })
+            }
+            None => info!("No repository registered yet, add one with 'zr repo add <url>'"),
+        }
+    }
+
+    /// Rejects anything that clearly isn't a remote: blank, or without a recognizable
+    /// scheme (`https://`, `http://`, `ssh://`, `git://`) or scp-like (`[email protected]:org/repo`)
+    /// syntax, once any `git+`/`hg+`/`fossil+` [`Backend`][crate::git::backend::Backend] prefix
+    /// is stripped off
+    fn is_valid_url(url: &str) -> bool {
+        let (_, url) = resolve(url);
+        let has_scheme = ["https://", "http://", "ssh://", "git://"].iter().any(|scheme| url.starts_with(scheme));
+        !url.trim().is_empty() && (has_scheme || GitRemote::from(url).is_ssh())
+    }
+}
+
+#[cfg(test)]
+mod repo_actions_tests {
+    use std::ops::Not;
+
+    use super::*;
+
+    #[test]
+    fn should_validate_https_url() {
+        assert!(RepoActions::is_valid_url("https://github.com/beltram/my-zr-config.git"));
+    }
+
+    #[test]
+    fn should_validate_scp_like_url() {
+        assert!(RepoActions::is_valid_url("[email protected]:beltram/my-zr-config.git"));
+    }
+
+    #[test]
+    fn should_reject_blank_url() {
+        assert!(RepoActions::is_valid_url("").not());
+        assert!(RepoActions::is_valid_url("   ").not());
+    }
+
+    #[test]
+    fn should_reject_schemeless_garbage() {
+        assert!(RepoActions::is_valid_url("not-a-url").not());
+    }
+
+    #[test]
+    fn should_validate_url_with_backend_prefix() {
+        assert!(RepoActions::is_valid_url("hg+https://hg.example.org/my-zr-config"));
+        assert!(RepoActions::is_valid_url("fossil+https://fossil.example.org/my-zr-config"));
+    }
+}
@@ -1,6 +1,10 @@
+use rkyv::{Archive, Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
 use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+use super::predicate::ArgPredicate;
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Archive, RkyvSerialize, RkyvDeserialize)]
+#[archive(check_bytes)]
 pub enum LocalArgKind {
     /// A boolean arg, taking no value e.g. '--force'
     #[serde(rename_all = "kebab-case")]
@@ -12,12 +16,17 @@ pub enum LocalArgKind {
     ARG {
         default: Option<String>,
         possible_values: Option<Vec<String>>,
+        /// Env var whose value supplies the default when the flag is absent, taking
+        /// precedence over the literal `default` (used as a fallback when it's unset)
+        env: Option<String>,
     },
     /// An arg having multiple occurrences e.g. '--mod=api --mod=error'
     #[serde(rename_all = "kebab-case")]
     MULTI {
         default: Option<Vec<String>>,
         possible_values: Option<Vec<String>>,
+        /// Same semantics as `ARG::env`, split on the same delimiter as `RequireDelimiter`
+        env: Option<String>,
     },
     /// A command execute after project creation
     #[serde(rename_all = "kebab-case")]
@@ -25,11 +34,14 @@ pub enum LocalArgKind {
         order: Option<u8>,
         default: Option<bool>,
         cmd: String,
+        /// Only run `cmd` when another arg's resolved value matches this predicate,
+        /// evaluated in addition to (not instead of) `default`/presence
+        predicate: Option<ArgPredicate>,
     },
 }
 
 impl Default for LocalArgKind {
     fn default() -> Self {
-        Self::ARG { default: None, possible_values: None }
+        Self::ARG { default: None, possible_values: None, env: None }
     }
 }
\ No newline at end of file
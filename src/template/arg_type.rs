@@ -0,0 +1,110 @@
+use rkyv::{Archive, Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::utils::anyhow_err::OptConversion;
+
+/// The declared value type of a template arg, used to coerce and validate the raw
+/// string matched by clap before it lands in `InitializrData.args`
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Archive, RkyvSerialize, RkyvDeserialize)]
+#[archive(check_bytes)]
+#[serde(rename_all = "kebab-case")]
+pub enum ArgType {
+    String,
+    Int,
+    Bool,
+    #[serde(rename_all = "kebab-case")]
+    Enum { values: Vec<String> },
+}
+
+impl Default for ArgType {
+    fn default() -> Self { Self::String }
+}
+
+impl ArgType {
+    pub fn coerce(&self, raw: &str) -> anyhow::Result<Value> {
+        match self {
+            Self::String => Ok(Value::from(raw)),
+            Self::Int => raw.parse::<i64>().ok()
+                .map(Value::from)
+                .wrap(format!("'{}' is not a valid integer", raw)),
+            Self::Bool => match raw {
+                "true" | "1" => Ok(Value::from(true)),
+                "false" | "0" => Ok(Value::from(false)),
+                _ => Err(anyhow::Error::msg(format!("'{}' is not a valid boolean, expected one of [true, false, 1, 0]", raw))),
+            },
+            Self::Enum { values } => {
+                if values.iter().any(|it| it == raw) {
+                    Ok(Value::from(raw))
+                } else {
+                    Err(anyhow::Error::msg(format!("'{}' is not one of the allowed values: [{}]", raw, values.join(", "))))
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod arg_type_tests {
+    use super::*;
+
+    mod string {
+        use super::*;
+
+        #[test]
+        fn should_coerce_any_value() {
+            assert_eq!(ArgType::String.coerce("anything").unwrap(), Value::from("anything"));
+        }
+    }
+
+    mod int {
+        use super::*;
+
+        #[test]
+        fn should_coerce_valid_int() {
+            assert_eq!(ArgType::Int.coerce("42").unwrap(), Value::from(42));
+        }
+
+        #[test]
+        fn should_fail_coercing_invalid_int() {
+            assert!(ArgType::Int.coerce("not-a-number").is_err());
+        }
+    }
+
+    mod bool {
+        use super::*;
+
+        #[test]
+        fn should_coerce_true_variants() {
+            assert_eq!(ArgType::Bool.coerce("true").unwrap(), Value::from(true));
+            assert_eq!(ArgType::Bool.coerce("1").unwrap(), Value::from(true));
+        }
+
+        #[test]
+        fn should_coerce_false_variants() {
+            assert_eq!(ArgType::Bool.coerce("false").unwrap(), Value::from(false));
+            assert_eq!(ArgType::Bool.coerce("0").unwrap(), Value::from(false));
+        }
+
+        #[test]
+        fn should_fail_coercing_invalid_bool() {
+            assert!(ArgType::Bool.coerce("maybe").is_err());
+        }
+    }
+
+    mod r#enum {
+        use super::*;
+
+        #[test]
+        fn should_coerce_value_within_set() {
+            let ty = ArgType::Enum { values: vec![String::from("a"), String::from("b")] };
+            assert_eq!(ty.coerce("a").unwrap(), Value::from("a"));
+        }
+
+        #[test]
+        fn should_fail_coercing_value_outside_set() {
+            let ty = ArgType::Enum { values: vec![String::from("a"), String::from("b")] };
+            assert!(ty.coerce("c").is_err());
+        }
+    }
+}
@@ -0,0 +1,170 @@
+//! Persistent rkyv-archived cache for parsed `zr.toml` template configs. A hit is
+//! validated then deserialized into an owned `LocalInitializrArgs`; it is not read in
+//! place (zero-copy).
+use std::{fs, path::{Path, PathBuf}, time::UNIX_EPOCH};
+
+use rkyv::{check_archived_root, Infallible};
+
+use crate::config::hash::ConfigHash;
+use crate::utils::marshall::Tomlable;
+use crate::utils::zr::Zr;
+
+use super::local_args::LocalInitializrArgs;
+
+/// Caches the parsed form of a `zr.toml` as an rkyv archive so large template trees
+/// don't pay a fresh TOML parse on every invocation.
+///
+/// Entries are keyed by a hash of the source file's absolute path combined with its
+/// mtime and size, so a changed `zr.toml` is transparently treated as a cache miss.
+pub struct CacheStore;
+
+impl CacheStore {
+    const CACHE_DIR: &'static str = "config-cache";
+    const SOURCE_EXTENSION: &'static str = "src";
+
+    /// Returns the parsed config for `source`, reading it from the on-disk archive
+    /// when still valid, or parsing it from TOML (and rewriting the archive) otherwise.
+    pub fn get_or_parse(source: &Path) -> Option<LocalInitializrArgs> {
+        let cache_path = Self::cache_path(source);
+        if let Some(path) = cache_path.as_ref() {
+            if let Some(cached) = Self::read(path) {
+                return Some(cached);
+            }
+        }
+        // Only swept on a miss, which is already paying for a full TOML reparse ; a hit
+        // (the overwhelmingly common case across a large template tree) stays a single
+        // archive read with no extra directory walk.
+        Self::evict_orphaned();
+        let parsed = LocalInitializrArgs::from_file(source).ok()?;
+        if let Some(path) = cache_path.as_ref() {
+            Self::write(path, source, &parsed);
+        }
+        Some(parsed)
+    }
+
+    /// Computes the cache slot for `source`. Returns `None` (a guaranteed miss) when
+    /// `source` no longer exists ; its stale entry, if any, is swept by `evict_orphaned`.
+    fn cache_path(source: &Path) -> Option<PathBuf> {
+        if !source.exists() {
+            return None;
+        }
+        let fingerprint = Self::fingerprint(source)?;
+        Zr::home().map(|it| it.join(Self::CACHE_DIR).join((&fingerprint).config_hash()))
+    }
+
+    fn fingerprint(source: &Path) -> Option<String> {
+        let absolute = fs::canonicalize(source).ok()?;
+        let metadata = fs::metadata(&absolute).ok()?;
+        let mtime = metadata.modified().ok()?.duration_since(UNIX_EPOCH).ok()?.as_nanos();
+        Some(format!("{}-{}-{}", absolute.to_string_lossy(), mtime, metadata.len()))
+    }
+
+    /// Removes every cached entry whose recorded source file no longer exists, so a
+    /// template tree that gets reorganized or deleted doesn't accumulate orphaned
+    /// archives under `CACHE_DIR` forever. Each entry's source path is tracked in a
+    /// sidecar `.src` file alongside its archive, since the hash alone (derived from the
+    /// source's own mtime/size) can't be reversed back into a path once that file is gone.
+    fn evict_orphaned() {
+        let cache_dir = match Zr::home().map(|it| it.join(Self::CACHE_DIR)) {
+            Some(dir) if dir.exists() => dir,
+            _ => return,
+        };
+        let entries = match fs::read_dir(&cache_dir) {
+            Ok(entries) => entries,
+            Err(_) => return,
+        };
+        entries.filter_map(Result::ok)
+            .map(|it| it.path())
+            .filter(|it| it.extension().and_then(|ext| ext.to_str()) == Some(Self::SOURCE_EXTENSION))
+            .for_each(|source_marker| {
+                let recorded_source = fs::read_to_string(&source_marker).unwrap_or_default();
+                if !Path::new(&recorded_source).exists() {
+                    let _ = fs::remove_file(&source_marker);
+                    let _ = fs::remove_file(source_marker.with_extension(""));
+                }
+            });
+    }
+
+    fn read(cache_path: &Path) -> Option<LocalInitializrArgs> {
+        let bytes = fs::read(cache_path).ok()?;
+        let archived = check_archived_root::<LocalInitializrArgs>(bytes.as_slice()).ok()?;
+        rkyv::Deserialize::deserialize(archived, &mut Infallible).ok()
+    }
+
+    fn write(cache_path: &Path, source: &Path, value: &LocalInitializrArgs) {
+        if let Some(parent) = cache_path.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        if let Ok(bytes) = rkyv::to_bytes::<_, 256>(value) {
+            let _ = fs::write(cache_path, bytes);
+            if let Ok(absolute) = fs::canonicalize(source) {
+                let _ = fs::write(cache_path.with_extension(Self::SOURCE_EXTENSION), absolute.to_string_lossy().as_bytes());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod cache_store_tests {
+    use crate::config::hash::ConfigHash;
+    use crate::mocks::MockFs;
+    use crate::utils::file::PathExt;
+
+    use super::*;
+
+    fn sample(root: &Path) -> PathBuf {
+        let file = root.join("zr.toml");
+        file.write_to(r#"
+        [spring-boot-version]
+        kind = { ARG = { default = '2.4.0' } }
+        "#);
+        file
+    }
+
+    #[test]
+    fn should_parse_then_serve_from_cache() {
+        let root = MockFs::home().join("cache-hit");
+        root.create_dir_all_or_fail();
+        let config = sample(&root);
+        let parsed = CacheStore::get_or_parse(&config).unwrap();
+        let cached = CacheStore::get_or_parse(&config).unwrap();
+        assert_eq!(parsed, cached);
+    }
+
+    #[test]
+    fn should_invalidate_when_file_changes() {
+        let root = MockFs::home().join("cache-invalidate");
+        root.create_dir_all_or_fail();
+        let config = sample(&root);
+        CacheStore::get_or_parse(&config).unwrap();
+        config.write_to(r#"
+        [kotlin-version]
+        kind = { ARG = { default = '1.4.30' } }
+        "#);
+        let reparsed = CacheStore::get_or_parse(&config).unwrap();
+        assert!(reparsed.0.get("kotlin-version").is_some());
+    }
+
+    #[test]
+    fn should_evict_orphaned_entry_when_source_file_deleted() {
+        let root = MockFs::home().join("cache-evict");
+        root.create_dir_all_or_fail();
+        let config = sample(&root);
+        CacheStore::get_or_parse(&config).unwrap();
+        let fingerprint = CacheStore::fingerprint(&config).unwrap();
+        let cache_path = Zr::home().unwrap().join(CacheStore::CACHE_DIR).join((&fingerprint).config_hash());
+        let source_marker = cache_path.with_extension(CacheStore::SOURCE_EXTENSION);
+        assert!(cache_path.exists());
+        assert!(source_marker.exists());
+
+        config.delete().unwrap();
+        // A subsequent cache miss sweeps orphaned entries as a side effect
+        let other_root = MockFs::home().join("cache-evict-other");
+        other_root.create_dir_all_or_fail();
+        let other_config = sample(&other_root);
+        CacheStore::get_or_parse(&other_config).unwrap();
+
+        assert!(!cache_path.exists());
+        assert!(!source_marker.exists());
+    }
+}
@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+use handlebars::Handlebars;
+
+use crate::utils::marshall::Tomlable;
+
+/// A `---`-fenced TOML header a template file can start with, generalizing the old
+/// hard-coded README exclusion into a composable mechanism: `when` gates whether the file
+/// is generated at all and `to` overrides its destination path independently of the
+/// source path. Both fields are plain strings since they're Handlebars-rendered against
+/// the resolved [`crate::data::InitializrData`] before being parsed as TOML, so authors
+/// can write `when = "{{some-flag}}"` or `to = "{{proj}}/README.md"`.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct FrontMatter {
+    pub when: Option<String>,
+    pub to: Option<String>,
+}
+
+impl Tomlable for FrontMatter {}
+
+impl FrontMatter {
+    const FENCE: &'static str = "---";
+
+    /// Splits `source` into its front-matter-stripped body and the raw header text, when
+    /// `source` opens with a line matching the fence and a later line closes it. Returns
+    /// `source` unchanged and no header otherwise, so a file with a stray leading `---`
+    /// and no closing fence is left untouched rather than partially consumed.
+    pub fn split(source: &str) -> (String, Option<String>) {
+        let mut lines = source.lines();
+        if lines.next() != Some(Self::FENCE) {
+            return (source.to_string(), None);
+        }
+        let rest = lines.collect::<Vec<_>>();
+        match rest.iter().position(|line| *line == Self::FENCE) {
+            Some(end) => (rest[end + 1..].join("\n"), Some(rest[..end].join("\n"))),
+            None => (source.to_string(), None),
+        }
+    }
+
+    /// Handlebars-renders the raw header text against `data` then parses the result as
+    /// TOML, so a `when`/`to` field carrying a placeholder resolves before evaluation.
+    pub fn resolve<D: Serialize>(header: &str, handlebar: &Handlebars, data: &D) -> Self {
+        handlebar.render_template(header, data)
+            .map(Self::from_toml)
+            .unwrap_or_default()
+    }
+
+    /// Whether `when` (if set) rendered to anything but an explicit falsey value, mirroring
+    /// how an absent `.cfg()` marker always matches: no `when` field means always render.
+    pub fn should_render(&self) -> bool {
+        self.when.as_deref().map(|it| !matches!(it.trim(), "" | "false")).unwrap_or(true)
+    }
+}
+
+#[cfg(test)]
+mod front_matter_tests {
+    use super::*;
+
+    #[test]
+    fn should_leave_plain_body_unchanged_without_fence() {
+        let (body, header) = FrontMatter::split("plain content");
+        assert_eq!(body, "plain content");
+        assert!(header.is_none());
+    }
+
+    #[test]
+    fn should_split_header_from_body() {
+        let source = "---\nwhen = \"yes\"\nto = \"out.txt\"\n---\nthe body";
+        let (body, header) = FrontMatter::split(source);
+        assert_eq!(body, "the body");
+        assert_eq!(header, Some(String::from("when = \"yes\"\nto = \"out.txt\"")));
+    }
+
+    #[test]
+    fn should_leave_unchanged_when_fence_never_closes() {
+        let source = "---\nwhen = \"yes\"\nthe body";
+        let (body, header) = FrontMatter::split(source);
+        assert_eq!(body, source);
+        assert!(header.is_none());
+    }
+
+    #[test]
+    fn should_render_by_default_without_when() {
+        assert!(FrontMatter::default().should_render());
+    }
+
+    #[test]
+    fn should_not_render_on_explicit_false_or_empty_when() {
+        assert!(!FrontMatter { when: Some(String::from("false")), to: None }.should_render());
+        assert!(!FrontMatter { when: Some(String::from("")), to: None }.should_render());
+    }
+
+    #[test]
+    fn should_render_on_any_other_when_value() {
+        assert!(FrontMatter { when: Some(String::from("true")), to: None }.should_render());
+        assert!(FrontMatter { when: Some(String::from("cli")), to: None }.should_render());
+    }
+
+    #[test]
+    fn should_resolve_placeholders_before_parsing_toml() {
+        let handlebar = Handlebars::new();
+        let data = serde_json::json!({"flag": "cli", "proj": "my-app"});
+        let front_matter = FrontMatter::resolve("when = \"{{flag}}\"\nto = \"{{proj}}/README.md\"", &handlebar, &data);
+        assert_eq!(front_matter.when.as_deref(), Some("cli"));
+        assert_eq!(front_matter.to.as_deref(), Some("my-app/README.md"));
+    }
+}
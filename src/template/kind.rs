@@ -13,7 +13,9 @@ impl<'a> From<AppKind<'a>> for App<'a> {
             .about(InitializrTemplate::ABOUT_KIND);
         app = app.arg(InitializrStdArgs::project_name_arg());
         if let Some(f) = kind.1 {
-            app = app.args(Vec::<Arg>::from(f))
+            app = app.args(Vec::<Arg>::from(f));
+            app = app.subcommands(f.promoted().map(App::from));
+            app = f.groups().into_iter().fold(app, App::group);
         }
         let std_args = InitializrStdArgs::into_app();
         let std_args = std_args.get_arguments().collect_vec();
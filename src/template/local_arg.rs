@@ -1,18 +1,42 @@
-use clap::{Arg, ArgSettings};
+use clap::{App, Arg, ArgSettings};
 use itertools::Itertools;
+use rkyv::{Archive, Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 use LocalArgKind::{ARG, CMD, FLAG, MULTI};
 
-use super::{arg_kind::LocalArgKind, super::data::arg_cmd::ArgCmd};
+use crate::utils::cfg_expr::{CfgExpr, host_platform_active};
+use crate::utils::error::ErrorExt;
 
-#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq)]
+use super::{arg_kind::LocalArgKind, arg_type::ArgType, local_args::LocalInitializrArgs, predicate::DefaultValueIf, super::data::arg_cmd::ArgCmd};
+
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Archive, RkyvSerialize, RkyvDeserialize)]
+#[archive(check_bytes)]
 #[serde(default)]
 pub struct LocalInitializrArg {
     pub about: Option<String>,
     pub short: Option<char>,
     pub long: Option<String>,
     pub kind: Option<LocalArgKind>,
+    /// A cfg-expression (`cfg = 'target_os = "macos"'`) gating this arg to matching hosts
+    pub cfg: Option<String>,
+    /// The declared value type (`string`, `int`, `bool`, `enum { values = [...] }`),
+    /// used to coerce and validate the matched value. Defaults to `string`
+    #[serde(rename = "type")]
+    pub value_type: Option<ArgType>,
+    /// Other arg names that must also be present when this arg is
+    pub requires: Option<Vec<String>>,
+    /// Other arg names that must be absent when this arg is present
+    pub conflicts_with: Option<Vec<String>>,
+    /// Injects a default for this arg when another arg is present (optionally with a given value)
+    pub default_value_if: Option<DefaultValueIf>,
+    /// Promotes a `CMD` arg from a standalone flag into a real nested subcommand, whose
+    /// own args/subcommands are declared here exactly like the root `zr.toml` args
+    pub subcommands: Option<LocalInitializrArgs>,
+    /// Places this arg in a named `ArgGroup` requiring exactly one of its members, resolved
+    /// in a second pass over the whole arg set once every individual `Arg` is built
+    pub group: Option<String>,
 }
 
 impl LocalInitializrArg {
@@ -22,6 +46,18 @@ impl LocalInitializrArg {
         is_default_cmd || is_default_flag
     }
 
+    /// Whether this arg's `cfg` expression (if any) matches the current host.
+    /// An invalid expression is treated as a warning and drops the arg (returns `false`).
+    pub fn matches_platform(&self) -> bool {
+        match self.cfg.as_ref() {
+            None => true,
+            Some(raw) => CfgExpr::parse(raw)
+                .else_warn(format!("Invalid cfg() expression '{}', dropping arg", raw))
+                .map(|expr| expr.eval(&host_platform_active()))
+                .unwrap_or(false),
+        }
+    }
+
     pub fn is_multi(&self) -> bool {
         if let Some(MULTI { .. }) = self.kind.as_ref() {
             return true;
@@ -30,11 +66,12 @@ impl LocalInitializrArg {
     }
 
     pub fn maybe_cmd(&self) -> Option<ArgCmd> {
-        if let Some(CMD { order, default, cmd, .. }) = self.kind.as_ref() {
+        if let Some(CMD { order, default, cmd, predicate }) = self.kind.as_ref() {
             return Some(ArgCmd {
                 cmd: cmd.to_string(),
                 order: order.unwrap_or_default(),
                 is_default: default.unwrap_or_default(),
+                predicate: predicate.clone(),
             });
         }
         None
@@ -53,6 +90,35 @@ impl LocalInitializrArg {
         }
         false
     }
+
+    /// Validates & coerces a matched value against this arg's declared `type`.
+    /// For a multi-arg (`value` is a JSON array), every element is coerced individually.
+    pub fn coerce(&self, value: Value) -> anyhow::Result<Value> {
+        let ty = self.value_type.clone().unwrap_or_default();
+        match value {
+            Value::Array(values) => {
+                values.into_iter()
+                    .map(|it| Self::coerce_one(&ty, it))
+                    .collect::<anyhow::Result<Vec<_>>>()
+                    .map(Value::from)
+            }
+            other => Self::coerce_one(&ty, other),
+        }
+    }
+
+    /// The declared default value, for `ARG` args only (used as the pre-filled answer
+    /// when interactively prompting for a missing value)
+    pub fn default_value(&self) -> Option<String> {
+        if let Some(ARG { default, .. }) = self.kind.as_ref() {
+            return default.clone();
+        }
+        None
+    }
+
+    fn coerce_one(ty: &ArgType, value: Value) -> anyhow::Result<Value> {
+        let raw = value.as_str().ok_or_else(|| anyhow::Error::msg(format!("Expected a string value, got '{}'", value)))?;
+        ty.coerce(raw)
+    }
 }
 
 impl Default for LocalInitializrArg {
@@ -62,6 +128,13 @@ impl Default for LocalInitializrArg {
             short: None,
             long: None,
             kind: Some(LocalArgKind::default()),
+            cfg: None,
+            value_type: None,
+            requires: None,
+            conflicts_with: None,
+            default_value_if: None,
+            subcommands: None,
+            group: None,
         }
     }
 }
@@ -69,7 +142,7 @@ impl Default for LocalInitializrArg {
 pub(crate) struct NamedArg<'a>(pub(crate) &'a str, pub(crate) &'a LocalInitializrArg);
 
 impl NamedArg<'_> {
-    const CMD_HELP_HEADING: &'static str = "COMMANDS";
+    pub(crate) const CMD_HELP_HEADING: &'static str = "COMMANDS";
 
     fn flatten(of: &Option<Vec<String>>) -> Option<Vec<&str>> {
         of.as_ref().map(|it| it.iter().map(|i| i.as_str()).collect_vec())
@@ -78,22 +151,22 @@ impl NamedArg<'_> {
     fn map_kind<'a>(mut arg: Arg<'a>, kind: &'a LocalArgKind) -> Arg<'a> {
         match kind {
             FLAG { .. } => arg = arg.required(false).takes_value(false),
-            ARG { default, possible_values } => {
+            ARG { default, possible_values, env } => {
                 arg = arg.takes_value(true);
-                if let Some(default) = default {
-                    arg = arg.default_value(default.as_str())
+                if let Some(default) = Self::env_default(default, env) {
+                    arg = arg.default_value(default)
                 }
                 if let Some(possible_values) = NamedArg::flatten(possible_values) {
                     arg = arg.possible_values(possible_values.as_slice())
                 }
             }
-            MULTI { default, possible_values } => {
+            MULTI { default, possible_values, env } => {
                 arg = arg
                     .takes_value(true)
                     .multiple(true)
                     .multiple_occurrences(true)
                     .setting(ArgSettings::RequireDelimiter);
-                if let Some(default) = NamedArg::flatten(default) {
+                if let Some(default) = Self::env_multi_default(default, env) {
                     arg = arg.default_values(default.as_slice())
                 }
                 if let Some(possible_values) = NamedArg::flatten(possible_values) {
@@ -109,6 +182,29 @@ impl NamedArg<'_> {
         }
         arg
     }
+
+    /// Resolves `env`'s current value as the default, falling back to the literal `default`
+    /// only when the env var is unset. The env var's value outlives the parsed config in
+    /// practice (it's read once at startup and the process exits after), so it's leaked to
+    /// satisfy the `'a` lifetime clap's `Arg::default_value` requires.
+    fn env_default<'a>(default: &'a Option<String>, env: &Option<String>) -> Option<&'a str> {
+        match env.as_ref().and_then(|name| std::env::var(name).ok()) {
+            Some(value) => Some(Box::leak(value.into_boxed_str())),
+            None => default.as_deref(),
+        }
+    }
+
+    /// Same as [`Self::env_default`] for `MULTI`, splitting the env var's value on the same
+    /// delimiter `ArgSettings::RequireDelimiter` uses to split repeated/joined occurrences
+    fn env_multi_default<'a>(default: &'a Option<Vec<String>>, env: &Option<String>) -> Option<Vec<&'a str>> {
+        match env.as_ref().and_then(|name| std::env::var(name).ok()) {
+            Some(value) => {
+                let leaked: &'a str = Box::leak(value.into_boxed_str());
+                Some(leaked.split(',').collect_vec())
+            }
+            None => NamedArg::flatten(default),
+        }
+    }
 }
 
 impl<'a> From<NamedArg<'a>> for Arg<'a> {
@@ -128,10 +224,39 @@ impl<'a> From<NamedArg<'a>> for Arg<'a> {
         if let Some(kind) = flag.kind.as_ref() {
             arg = NamedArg::map_kind(arg, kind)
         }
+        if let Some(conflicts) = NamedArg::flatten(&flag.conflicts_with) {
+            arg = arg.conflicts_with_all(conflicts.as_slice());
+        }
+        if let Some(requires) = NamedArg::flatten(&flag.requires) {
+            arg = arg.requires_all(requires.as_slice());
+        }
+        if let Some(group) = flag.group.as_ref() {
+            arg = arg.group(group.as_str());
+        }
         arg
     }
 }
 
+/// A `CMD` arg carrying a `subcommands` tree, promoted from a valueless flag into a real
+/// nested clap subcommand so it can own its own args/subcommands instead of just gating
+/// a post-bootstrap shell command on its presence.
+pub(crate) struct NamedSubcommand<'a>(pub(crate) &'a str, pub(crate) &'a LocalInitializrArg);
+
+impl<'a> From<NamedSubcommand<'a>> for App<'a> {
+    fn from(NamedSubcommand(name, arg): NamedSubcommand<'a>) -> Self {
+        let mut app = App::new(name);
+        if let Some(about) = arg.about.as_ref() {
+            app = app.about(about.as_str());
+        }
+        if let Some(children) = arg.subcommands.as_ref() {
+            app = app.args(Vec::<Arg>::from(children));
+            app = app.subcommands(children.promoted().map(App::from));
+            app = children.groups().into_iter().fold(app, App::group);
+        }
+        app
+    }
+}
+
 #[cfg(test)]
 mod local_arg_tests {
     use clap::App;
@@ -139,6 +264,81 @@ mod local_arg_tests {
 
     use super::*;
 
+    mod cfg {
+        use super::*;
+
+        #[test]
+        fn should_match_when_no_cfg() {
+            let arg = LocalInitializrArg::default();
+            assert!(arg.matches_platform());
+        }
+
+        #[test]
+        fn should_match_current_family() {
+            let family = std::env::consts::FAMILY;
+            let arg = LocalInitializrArg { cfg: Some(family.to_string()), ..Default::default() };
+            assert!(arg.matches_platform());
+        }
+
+        #[test]
+        fn should_not_match_other_family() {
+            let other = if std::env::consts::FAMILY == "unix" { "windows" } else { "unix" };
+            let arg = LocalInitializrArg { cfg: Some(other.to_string()), ..Default::default() };
+            assert!(!arg.matches_platform());
+        }
+
+        #[test]
+        fn should_drop_arg_on_invalid_cfg_expression() {
+            let arg = LocalInitializrArg { cfg: Some(String::from("not(")), ..Default::default() };
+            assert!(!arg.matches_platform());
+        }
+    }
+
+    mod value_type {
+        use super::*;
+
+        #[test]
+        fn should_coerce_as_string_by_default() {
+            let arg = LocalInitializrArg::default();
+            assert_eq!(arg.coerce(Value::from("abc")).unwrap(), Value::from("abc"));
+        }
+
+        #[test]
+        fn should_coerce_as_int() {
+            let arg = LocalInitializrArg { value_type: Some(ArgType::Int), ..Default::default() };
+            assert_eq!(arg.coerce(Value::from("42")).unwrap(), Value::from(42));
+        }
+
+        #[test]
+        fn should_fail_coercing_invalid_int() {
+            let arg = LocalInitializrArg { value_type: Some(ArgType::Int), ..Default::default() };
+            assert!(arg.coerce(Value::from("nope")).is_err());
+        }
+
+        #[test]
+        fn should_fail_coercing_value_outside_enum() {
+            let arg = LocalInitializrArg {
+                value_type: Some(ArgType::Enum { values: vec![String::from("a"), String::from("b")] }),
+                ..Default::default()
+            };
+            assert!(arg.coerce(Value::from("c")).is_err());
+        }
+
+        #[test]
+        fn should_coerce_each_element_of_a_multi_arg() {
+            let arg = LocalInitializrArg { value_type: Some(ArgType::Int), ..Default::default() };
+            let values = Value::from(vec![Value::from("1"), Value::from("2")]);
+            assert_eq!(arg.coerce(values).unwrap(), Value::from(vec![Value::from(1), Value::from(2)]));
+        }
+
+        #[test]
+        fn should_fail_coercing_multi_arg_when_one_element_invalid() {
+            let arg = LocalInitializrArg { value_type: Some(ArgType::Int), ..Default::default() };
+            let values = Value::from(vec![Value::from("1"), Value::from("nope")]);
+            assert!(arg.coerce(values).is_err());
+        }
+    }
+
     mod std {
         use super::*;
 
@@ -192,7 +392,7 @@ mod local_arg_tests {
         #[test]
         fn should_not_be_required_by_default() {
             let arg = LocalInitializrArg {
-                kind: Some(ARG { default: None, possible_values: None }),
+                kind: Some(ARG { default: None, possible_values: None, env: None }),
                 ..Default::default()
             };
             let arg = Arg::from(NamedArg("opt", &arg));
@@ -203,7 +403,7 @@ mod local_arg_tests {
         #[test]
         fn should_not_require_equals() {
             let arg = LocalInitializrArg {
-                kind: Some(ARG { default: None, possible_values: None }),
+                kind: Some(ARG { default: None, possible_values: None, env: None }),
                 ..Default::default()
             };
             let arg = Arg::from(NamedArg("opt", &arg));
@@ -217,6 +417,7 @@ mod local_arg_tests {
                 kind: Some(ARG {
                     default: Some(String::from("abba")),
                     possible_values: None,
+                    env: None,
                 }),
                 ..Default::default()
             };
@@ -232,6 +433,7 @@ mod local_arg_tests {
                 kind: Some(ARG {
                     default: None,
                     possible_values: Some(vec![String::from("a"), String::from("b")]),
+                    env: None,
                 }),
                 ..Default::default()
             };
@@ -268,7 +470,7 @@ mod local_arg_tests {
 
         #[test]
         fn should_not_be_required_and_take_no_value_when_kind_cmd() {
-            let cmd = LocalInitializrArg { kind: Some(CMD { order: None, default: None, cmd: String::new() }), ..Default::default() };
+            let cmd = LocalInitializrArg { kind: Some(CMD { order: None, default: None, cmd: String::new(), predicate: None }), ..Default::default() };
             let arg = Arg::from(NamedArg("gradle", &cmd));
             let app = App::new("prog").arg(arg);
             // for required
@@ -279,7 +481,7 @@ mod local_arg_tests {
 
         #[test]
         fn cmd_should_have_help_heading() {
-            let cmd = LocalInitializrArg { kind: Some(CMD { order: None, default: None, cmd: String::new() }), ..Default::default() };
+            let cmd = LocalInitializrArg { kind: Some(CMD { order: None, default: None, cmd: String::new(), predicate: None }), ..Default::default() };
             let arg = Arg::from(NamedArg("gradle", &cmd));
             assert_eq!(arg.get_help_heading(), Some(NamedArg::CMD_HELP_HEADING))
         }
@@ -290,7 +492,7 @@ mod local_arg_tests {
 
         #[test]
         fn multi_should_not_be_required() {
-            let multi = LocalInitializrArg { kind: Some(MULTI { default: None, possible_values: None }), ..Default::default() };
+            let multi = LocalInitializrArg { kind: Some(MULTI { default: None, possible_values: None, env: None }), ..Default::default() };
             let arg = Arg::from(NamedArg("m", &multi));
             let app = App::new("prog").arg(arg);
             assert!(app.try_get_matches_from(vec!["prog"]).is_ok());
@@ -298,7 +500,7 @@ mod local_arg_tests {
 
         #[test]
         fn multi_should_be_repeatable() {
-            let multi = LocalInitializrArg { kind: Some(MULTI { default: None, possible_values: None }), ..Default::default() };
+            let multi = LocalInitializrArg { kind: Some(MULTI { default: None, possible_values: None, env: None }), ..Default::default() };
             let arg = Arg::from(NamedArg("m", &multi));
             let app = App::new("prog").arg(arg);
             assert_eq!(
@@ -317,6 +519,7 @@ mod local_arg_tests {
                 kind: Some(MULTI {
                     default: None,
                     possible_values: Some(vec![String::from("a"), String::from("b")]),
+                    env: None,
                 }),
                 ..Default::default()
             };
@@ -336,6 +539,7 @@ mod local_arg_tests {
                 kind: Some(MULTI {
                     default: Some(vec![String::from("api"), String::from("error")]),
                     possible_values: None,
+                    env: None,
                 }),
                 ..Default::default()
             };
@@ -18,35 +18,95 @@
 //! #     long: Some(String::from("spring-boot-version")),
 //! #     kind: Some(LocalArgKind::ARG {
 //! #         default: Some(String::from("2.4.0")),
-//! #         possible_values: Some(vec![String::from("2.4.0"), String::from("2.4.1")])
+//! #         possible_values: Some(vec![String::from("2.4.0"), String::from("2.4.1")]),
+//! #         env: None,
 //! #     }),
+//! #     cfg: None,
+//! #     value_type: None,
+//! #     requires: None,
+//! #     conflicts_with: None,
+//! #     default_value_if: None,
+//! #     subcommands: None,
+//! #     group: None,
 //! # };
 //! # let flags = BTreeMap::from_iter(vec![(String::from("spring-boot-version"), spring_boot_version)]);
 //! # assert_eq!(LocalInitializrArgs(flags), LocalInitializrArgs::from_toml(sample));
 //! ```
 use std::collections::BTreeMap;
 
-use clap::Arg;
+use clap::{Arg, ArgGroup, ArgMatches};
 use itertools::Itertools;
+use rkyv::{Archive, Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
 use serde::{Deserialize, Serialize};
 
 use crate::utils::marshall::Tomlable;
 
-use super::{local_arg::LocalInitializrArg, local_arg::NamedArg};
+use super::{local_arg::LocalInitializrArg, local_arg::NamedArg, local_arg::NamedSubcommand};
 
-#[derive(Clone, Debug, Serialize, Deserialize, Default, Eq, PartialEq)]
+#[derive(Clone, Debug, Serialize, Deserialize, Default, Eq, PartialEq, Archive, RkyvSerialize, RkyvDeserialize)]
+#[archive(check_bytes)]
 #[serde(transparent)]
 pub struct LocalInitializrArgs(pub BTreeMap<String, LocalInitializrArg>);
 
 impl<'a> From<&'a LocalInitializrArgs> for Vec<Arg<'a>> {
     fn from(args: &'a LocalInitializrArgs) -> Self {
         args.0.iter()
+            .filter(|(_, flag)| flag.matches_platform())
+            .filter(|(_, flag)| flag.subcommands.is_none())
             .map(|(name, flag)| NamedArg(name, flag))
             .map(Arg::from)
             .collect_vec()
     }
 }
 
+impl LocalInitializrArgs {
+    /// Entries promoted from a standalone `CMD` flag into a real nested subcommand,
+    /// i.e. declaring their own `subcommands` tree
+    pub(crate) fn promoted(&self) -> impl Iterator<Item=NamedSubcommand> {
+        self.0.iter()
+            .filter(|(_, arg)| arg.matches_platform())
+            .filter(|(_, arg)| arg.subcommands.is_some())
+            .map(|(name, arg)| NamedSubcommand(name, arg))
+    }
+
+    /// Groups args sharing the same `group` value into an `ArgGroup` requiring exactly one
+    /// of them. This needs a second pass over the whole set, after every individual `Arg` is
+    /// built, since a group spans multiple independently-constructed `Arg`s.
+    pub(crate) fn groups(&self) -> Vec<ArgGroup> {
+        self.0.iter()
+            .filter(|(_, arg)| arg.matches_platform())
+            .filter_map(|(name, arg)| arg.group.as_ref().map(|group| (group.as_str(), name.as_str())))
+            .into_group_map()
+            .into_iter()
+            .map(|(group, members)| ArgGroup::new(group).args(members.as_slice()).required(true))
+            .collect_vec()
+    }
+
+    /// Walks the matched subcommand chain, falling back to the child marked `default = true`
+    /// (lowest `order` wins on a tie) when none was explicitly passed on the command line,
+    /// mirroring the existing flag-based `CMD` default semantics. Returns the full selected
+    /// path (e.g. `["gradle-wrapper", "init"]`) so the scaffolding step can branch on it.
+    pub fn selected_subcommand(&self, matches: &ArgMatches) -> Option<Vec<String>> {
+        match matches.subcommand() {
+            Some((name, sub_matches)) => {
+                let mut path = vec![name.to_string()];
+                if let Some(children) = self.0.get(name).and_then(|it| it.subcommands.as_ref()) {
+                    if let Some(mut rest) = children.selected_subcommand(sub_matches) {
+                        path.append(&mut rest);
+                    }
+                }
+                Some(path)
+            }
+            None => self.0.iter()
+                .filter(|(_, arg)| arg.subcommands.is_some())
+                .filter(|(_, arg)| arg.maybe_cmd().map(|it| it.is_default).unwrap_or_default())
+                .sorted_by_key(|(_, arg)| arg.maybe_cmd().map(|it| it.order).unwrap_or_default())
+                .next()
+                .map(|(name, _)| vec![name.to_string()]),
+        }
+    }
+}
+
 impl Tomlable for LocalInitializrArgs {}
 
 #[cfg(test)]
@@ -74,17 +134,18 @@ mod local_args_tests {
         assert_eq!(spring_boot_version.long.as_ref().unwrap().as_str(), "sb-version");
         assert_eq!(
             spring_boot_version.kind.as_ref().unwrap(),
-            &LocalArgKind::ARG { default: None, possible_values: None }
+            &LocalArgKind::ARG { default: None, possible_values: None, env: None }
         );
         let spring_cloud_version = args.0.get("spring-cloud-version").unwrap();
         assert_eq!(spring_cloud_version.kind.as_ref().unwrap(), &LocalArgKind::ARG {
             default: None,
             possible_values: Some(vec![String::from("2020.0.0"), String::from("2020.0.1")]),
+            env: None,
         });
         let kotlin_version = args.0.get("kotlin-version").unwrap();
         assert_eq!(
             kotlin_version.kind.as_ref().unwrap(),
-            &LocalArgKind::ARG { default: Some(String::from("1.4.30")), possible_values: None }
+            &LocalArgKind::ARG { default: Some(String::from("1.4.30")), possible_values: None, env: None }
         );
     }
 
@@ -100,16 +161,18 @@ mod local_args_tests {
         "#);
         assert_eq!(args.0.len(), 3);
         let spring_boot_version = args.0.get("spring-boot-version").unwrap();
-        assert_eq!(spring_boot_version.kind.as_ref().unwrap(), &LocalArgKind::MULTI { default: None, possible_values: None });
+        assert_eq!(spring_boot_version.kind.as_ref().unwrap(), &LocalArgKind::MULTI { default: None, possible_values: None, env: None });
         let spring_cloud_version = args.0.get("spring-cloud-version").unwrap();
         assert_eq!(spring_cloud_version.kind.as_ref().unwrap(), &LocalArgKind::MULTI {
             default: None,
             possible_values: Some(vec![String::from("2020.0.0"), String::from("2020.0.1")]),
+            env: None,
         });
         let modules = args.0.get("modules").unwrap();
         assert_eq!(modules.kind.as_ref().unwrap(), &LocalArgKind::MULTI {
             default: Some(vec![String::from("api"), String::from("error"), String::from("kafka")]),
             possible_values: None,
+            env: None,
         });
     }
 
@@ -137,7 +200,7 @@ mod local_args_tests {
         "#);
         assert_eq!(args.0.len(), 1);
         let spring_boot_version = args.0.get("spring-boot-version").unwrap();
-        assert_eq!(spring_boot_version.kind.as_ref().unwrap(), &LocalArgKind::ARG { default: None, possible_values: None });
+        assert_eq!(spring_boot_version.kind.as_ref().unwrap(), &LocalArgKind::ARG { default: None, possible_values: None, env: None });
     }
 
     #[test]
@@ -156,12 +219,12 @@ mod local_args_tests {
         assert_eq!(args.0.len(), 4);
         let gradle_wrapper = args.0.get("gradle-wrapper").unwrap();
         assert_eq!(gradle_wrapper.about.as_ref().unwrap(), "Init a Gradle wrapper");
-        assert_eq!(gradle_wrapper.kind.as_ref().unwrap(), &LocalArgKind::CMD { order: None, default: None, cmd: String::from("gradle wrapper") });
+        assert_eq!(gradle_wrapper.kind.as_ref().unwrap(), &LocalArgKind::CMD { order: None, default: None, cmd: String::from("gradle wrapper"), predicate: None });
         let by_default = args.0.get("by-default").unwrap();
-        assert_eq!(by_default.kind.as_ref().unwrap(), &LocalArgKind::CMD { order: None, default: Some(true), cmd: String::from("ls .") });
+        assert_eq!(by_default.kind.as_ref().unwrap(), &LocalArgKind::CMD { order: None, default: Some(true), cmd: String::from("ls ."), predicate: None });
         let not_default = args.0.get("not-default").unwrap();
-        assert_eq!(not_default.kind.as_ref().unwrap(), &LocalArgKind::CMD { order: None, default: Some(false), cmd: String::from("ls .") });
+        assert_eq!(not_default.kind.as_ref().unwrap(), &LocalArgKind::CMD { order: None, default: Some(false), cmd: String::from("ls ."), predicate: None });
         let with_order = args.0.get("with-order").unwrap();
-        assert_eq!(with_order.kind.as_ref().unwrap(), &LocalArgKind::CMD { order: Some(1), default: None, cmd: String::from("ls .") });
+        assert_eq!(with_order.kind.as_ref().unwrap(), &LocalArgKind::CMD { order: Some(1), default: None, cmd: String::from("ls ."), predicate: None });
     }
 }
\ No newline at end of file
@@ -3,17 +3,23 @@ use std::path::PathBuf;
 use clap::App;
 use itertools::Itertools;
 
+use cache::CacheStore;
 use kind::AppKind;
 use local_args::LocalInitializrArgs;
 
-use crate::{completion::PreInitializrArgs, utils::marshall::Tomlable};
+use crate::completion::PreInitializrArgs;
 use crate::utils::error::ErrorExt;
 use crate::utils::file::PathExt;
 
 pub mod local_args;
 pub mod local_arg;
 pub mod arg_kind;
+pub mod arg_type;
+pub mod predicate;
+pub mod zrignore;
+pub mod front_matter;
 mod kind;
+mod cache;
 
 struct AppLang<'a>(String, AppKind<'a>);
 
@@ -58,7 +64,7 @@ impl InitializrTemplate {
             .map(|it| it.join(Self::CONFIG_FILE))
             .map(|local| {
                 let args = if local.exists() {
-                    if let Ok(mut local_args) = LocalInitializrArgs::from_file(&local) {
+                    if let Some(mut local_args) = CacheStore::get_or_parse(&local) {
                         if let Some(root) = root_args.clone().as_mut() {
                             local_args.0.append(&mut root.0);
                             Some(local_args)
@@ -74,16 +80,15 @@ impl InitializrTemplate {
         Some(self.root_path.join(Self::CONFIG_FILE))
             .filter(|it| it.exists())
             .and_then(|it| {
-                LocalInitializrArgs::from_file(it)
+                CacheStore::get_or_parse(&it)
                     .else_warn(format!("Invalid file format in {:?}/{}", self.root_path, Self::CONFIG_FILE))
-                    .ok()
             })
     }
 
     pub fn flag_of(path: PathBuf) -> Option<LocalInitializrArgs> {
         Some(path.join(Self::CONFIG_FILE))
             .filter(|it| it.exists())
-            .and_then(|it| LocalInitializrArgs::from_file(it).ok())
+            .and_then(|it| CacheStore::get_or_parse(&it))
     }
 
     fn all_templates(&self) -> Vec<(String, String)> {
@@ -0,0 +1,114 @@
+use rkyv::{Archive, Deserialize as RkyvDeserialize, Serialize as RkyvSerialize};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// Matches another arg's resolved value, mirroring clap's `Arg::default_value_if` predicate.
+/// Shared by [`DefaultValueIf`] (arg defaulting) and [`crate::template::arg_kind::LocalArgKind::CMD`]
+/// (command gating) so both read the same `{ arg, value }` shape from `zr.toml`.
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Archive, RkyvSerialize, RkyvDeserialize)]
+#[archive(check_bytes)]
+#[serde(rename_all = "kebab-case")]
+pub struct ArgPredicate {
+    pub arg: String,
+    /// When absent, triggers as soon as `arg` is present with any value
+    pub value: Option<String>,
+}
+
+impl ArgPredicate {
+    /// Evaluates this predicate against the final, already-coerced args map
+    pub fn matches(&self, args: &Map<String, Value>) -> bool {
+        match args.get(self.arg.as_str()) {
+            None => false,
+            Some(value) => self.value.as_ref()
+                .map(|expected| Self::value_matches(value, expected.as_str()))
+                .unwrap_or(true),
+        }
+    }
+
+    /// Compares `expected` (always a plain string straight out of `zr.toml`) against a JSON
+    /// scalar that may have been coerced to `Number`/`Bool` by the arg's `value_type`, so
+    /// comparing with `Value::as_str` alone would silently never match a non-string-typed
+    /// arg. Bools accept both `"true"`/`"false"` and `ArgType::Bool`'s `"1"`/`"0"` aliases.
+    fn value_matches(value: &Value, expected: &str) -> bool {
+        match value {
+            Value::String(s) => s == expected,
+            Value::Number(n) => n.to_string() == expected,
+            Value::Bool(b) => match expected {
+                "true" | "1" => *b,
+                "false" | "0" => !*b,
+                _ => false,
+            },
+            _ => false,
+        }
+    }
+}
+
+/// Injects a default value for this arg when another arg (optionally with a specific
+/// value) is present, mirroring clap's `Arg::default_value_if` predicate
+#[derive(Clone, Debug, Serialize, Deserialize, Eq, PartialEq, Archive, RkyvSerialize, RkyvDeserialize)]
+#[archive(check_bytes)]
+#[serde(rename_all = "kebab-case")]
+pub struct DefaultValueIf {
+    #[serde(flatten)]
+    pub predicate: ArgPredicate,
+    pub default: String,
+}
+
+#[cfg(test)]
+mod arg_predicate_tests {
+    use std::iter::FromIterator;
+
+    use super::*;
+
+    fn args(pairs: Vec<(&str, &str)>) -> Map<String, Value> {
+        Map::from_iter(pairs.into_iter().map(|(k, v)| (k.to_string(), Value::from(v))))
+    }
+
+    #[test]
+    fn should_match_when_arg_present_and_no_value_constraint() {
+        let predicate = ArgPredicate { arg: String::from("db"), value: None };
+        assert!(predicate.matches(&args(vec![("db", "postgres")])));
+    }
+
+    #[test]
+    fn should_not_match_when_arg_absent() {
+        let predicate = ArgPredicate { arg: String::from("db"), value: None };
+        assert!(!predicate.matches(&args(vec![])));
+    }
+
+    #[test]
+    fn should_match_when_value_equal() {
+        let predicate = ArgPredicate { arg: String::from("db"), value: Some(String::from("postgres")) };
+        assert!(predicate.matches(&args(vec![("db", "postgres")])));
+    }
+
+    #[test]
+    fn should_not_match_when_value_differs() {
+        let predicate = ArgPredicate { arg: String::from("db"), value: Some(String::from("postgres")) };
+        assert!(!predicate.matches(&args(vec![("db", "mysql")])));
+    }
+
+    #[test]
+    fn should_match_number_typed_arg_against_string_value() {
+        let predicate = ArgPredicate { arg: String::from("port"), value: Some(String::from("8080")) };
+        let mut args = Map::new();
+        args.insert(String::from("port"), Value::from(8080));
+        assert!(predicate.matches(&args));
+    }
+
+    #[test]
+    fn should_match_bool_typed_arg_against_string_value() {
+        let predicate = ArgPredicate { arg: String::from("verbose"), value: Some(String::from("true")) };
+        let mut args = Map::new();
+        args.insert(String::from("verbose"), Value::from(true));
+        assert!(predicate.matches(&args));
+    }
+
+    #[test]
+    fn should_match_bool_typed_arg_against_numeric_alias() {
+        let predicate = ArgPredicate { arg: String::from("verbose"), value: Some(String::from("1")) };
+        let mut args = Map::new();
+        args.insert(String::from("verbose"), Value::from(true));
+        assert!(predicate.matches(&args));
+    }
+}
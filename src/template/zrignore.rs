@@ -0,0 +1,156 @@
+use std::path::PathBuf;
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+
+use crate::utils::error::ErrorExt;
+use crate::utils::file::PathExt;
+
+/// A single `.zrignore` line, gitignore-style: `anchored` when the line started with `/`
+/// (must match from the template root instead of at any depth), `negated` when it started
+/// with `!` (re-includes a path an earlier pattern excluded), and a trailing `/` restricts
+/// the match to that directory and everything under it.
+struct Pattern {
+    glob: String,
+    anchored: bool,
+    negated: bool,
+}
+
+impl Pattern {
+    fn parse(line: &str) -> Self {
+        let negated = line.starts_with('!');
+        let line = line.strip_prefix('!').unwrap_or(line);
+        let anchored = line.starts_with('/');
+        let line = line.strip_prefix('/').unwrap_or(line);
+        let (stem, is_dir) = match line.strip_suffix('/') {
+            Some(stem) => (stem, true),
+            None => (line, false),
+        };
+        let rooted = if anchored { stem.to_string() } else { format!("**/{}", stem) };
+        let glob = if is_dir { format!("{}/**", rooted) } else { rooted };
+        Self { glob, anchored, negated }
+    }
+}
+
+/// Excludes template files from generation via a `.zrignore` file at the template root,
+/// gitignore-style: one glob per line, blank lines and `#` comments skipped. Patterns are
+/// evaluated in declaration order with last-match-wins: a later negated pattern re-includes
+/// a path an earlier pattern excluded, and vice versa.
+pub struct ZrIgnore {
+    excluded: GlobSet,
+    excluded_order: Vec<usize>,
+    included: GlobSet,
+    included_order: Vec<usize>,
+}
+
+impl ZrIgnore {
+    pub const FILE_NAME: &'static str = ".zrignore";
+
+    /// Loads `.zrignore` from `template_root`, or a match-nothing instance when absent
+    pub fn load(template_root: &PathBuf) -> Self {
+        let file = template_root.join(Self::FILE_NAME);
+        if !file.exists() {
+            return Self::compile(vec![]);
+        }
+        let patterns = file.lines().into_iter()
+            .map(|it| it.trim().to_string())
+            .filter(|it| !it.is_empty() && !it.starts_with('#'))
+            .map(|it| Pattern::parse(&it))
+            .collect::<Vec<_>>();
+        Self::compile(patterns)
+    }
+
+    fn compile(patterns: Vec<Pattern>) -> Self {
+        let mut excluded = GlobSetBuilder::new();
+        let mut excluded_order = vec![];
+        let mut included = GlobSetBuilder::new();
+        let mut included_order = vec![];
+        for (order, pattern) in patterns.iter().enumerate() {
+            let glob = match GlobBuilder::new(&pattern.glob).literal_separator(true).build()
+                .else_warn(format!("Invalid glob '{}' in {}, dropping it", pattern.glob, Self::FILE_NAME))
+                .ok() {
+                Some(glob) => glob,
+                None => continue,
+            };
+            if pattern.negated {
+                included.add(glob);
+                included_order.push(order);
+            } else {
+                excluded.add(glob);
+                excluded_order.push(order);
+            }
+        }
+        Self {
+            excluded: excluded.build().unwrap_or_else(|_| panic!("Invalid {}", Self::FILE_NAME)),
+            excluded_order,
+            included: included.build().unwrap_or_else(|_| panic!("Invalid {}", Self::FILE_NAME)),
+            included_order,
+        }
+    }
+
+    /// Whether `candidate` (a template-relative path, '/'-separated) should be skipped
+    pub fn is_ignored(&self, candidate: &str) -> bool {
+        let last_excluded = self.excluded.matches(candidate).into_iter().map(|it| self.excluded_order[it]).max();
+        let last_included = self.included.matches(candidate).into_iter().map(|it| self.included_order[it]).max();
+        match (last_excluded, last_included) {
+            (None, _) => false,
+            (Some(_), None) => true,
+            (Some(excluded), Some(included)) => excluded > included,
+        }
+    }
+}
+
+#[cfg(test)]
+mod zrignore_tests {
+    use super::*;
+
+    fn ignore(lines: &[&str]) -> ZrIgnore {
+        ZrIgnore::compile(lines.iter().map(|it| Pattern::parse(it)).collect())
+    }
+
+    #[test]
+    fn should_ignore_nothing_without_patterns() {
+        assert!(!ignore(&[]).is_ignored("src/main.rs"));
+    }
+
+    #[test]
+    fn should_match_unanchored_pattern_at_any_depth() {
+        let zrignore = ignore(&["*.log"]);
+        assert!(zrignore.is_ignored("debug.log"));
+        assert!(zrignore.is_ignored("nested/debug.log"));
+        assert!(!zrignore.is_ignored("debug.txt"));
+    }
+
+    #[test]
+    fn should_only_match_anchored_pattern_at_root() {
+        let zrignore = ignore(&["/target"]);
+        assert!(zrignore.is_ignored("target"));
+        assert!(!zrignore.is_ignored("nested/target"));
+    }
+
+    #[test]
+    fn should_match_whole_subtree_for_directory_pattern() {
+        let zrignore = ignore(&["build/"]);
+        assert!(zrignore.is_ignored("build/out.txt"));
+        assert!(zrignore.is_ignored("nested/build/out.txt"));
+        assert!(!zrignore.is_ignored("build"));
+    }
+
+    #[test]
+    fn should_apply_last_match_wins() {
+        let zrignore = ignore(&["*.log", "!keep.log"]);
+        assert!(zrignore.is_ignored("debug.log"));
+        assert!(!zrignore.is_ignored("keep.log"));
+    }
+
+    #[test]
+    fn should_allow_reverting_a_negation_with_a_later_pattern() {
+        let zrignore = ignore(&["*.log", "!keep.log", "keep.log"]);
+        assert!(zrignore.is_ignored("keep.log"));
+    }
+
+    #[test]
+    fn should_drop_malformed_pattern_instead_of_panicking() {
+        let zrignore = ignore(&["[unterminated", "*.log"]);
+        assert!(zrignore.is_ignored("debug.log"));
+    }
+}
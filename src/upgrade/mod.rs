@@ -13,6 +13,6 @@ impl ZrUpgrade {
     pub fn upgrade() {
         Config::upgrade(Config::get());
         info!("Updating {}", "completion files".green());
-        CliCompletion::apply(None);
+        CliCompletion::apply(None, false);
     }
 }
\ No newline at end of file
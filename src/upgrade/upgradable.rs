@@ -7,7 +7,9 @@ use crate::utils::zr::Zr;
 use crate::utils::error::ErrorExt;
 use crate::utils::file::PathExt;
 use crate::config::global::Config;
-use crate::git::Git;
+use crate::git::backend::resolve;
+use crate::git::GitRemote;
+use crate::utils::privilege::Privilege;
 
 pub trait Upgradable {
     const INSTALL_DIR: &'static str;
@@ -16,39 +18,50 @@ pub trait Upgradable {
     fn upgrade(config: Config) {
         info!("Updating {}", Self::NAME.green());
         Self::urls(&config).iter()
-            .for_each(|url| Self::update_one(url, url.config_hash()))
+            .for_each(|url| Self::update_one(url, url.config_hash(), config.ssh_key_for(url)))
     }
 
     fn urls(config: &Config) -> Vec<String>;
 
-    fn update_one(url: &str, hash: String) {
+    fn update_one(url: &str, hash: String, ssh_key: Option<&str>) {
         Self::install_dir()
             .wrap("Could not find installation dir")
             .map(|from| (from.join(hash), from))
             .and_then(|(into, from)| {
-                if into.exists() {
-                    Self::pull_rebase(&into)
+                let result = if into.exists() {
+                    Self::pull_rebase(&into, url)
                 } else {
-                    Self::install(&from, &into, url)
-                }
+                    Self::install(&from, &into, url, ssh_key)
+                };
+                result.map(|_| Privilege::chown_to_real_user(&into))
             })
             .warn(format!("Could not update from {}", url))
     }
 
-    fn install(path: &PathBuf, into_dir: &PathBuf, url: &str) -> anyhow::Result<()> {
+    fn install(path: &PathBuf, into_dir: &PathBuf, url: &str, ssh_key: Option<&str>) -> anyhow::Result<()> {
         debug!("Installing {} from {}", Self::NAME, url);
+        let (backend, uri) = resolve(url);
         into_dir.create_dir()
             .and_then(|_| into_dir.to_str().wrap("Failed acquiring target clone directory"))
             .and_then(|into| {
-                Git::clone(path, url, into, None, false)
-                    .wrap(format!("Failed cloning {} into {:?}", url, into))
+                backend.clone(path, uri, into, None, false, ssh_key)
+                    .wrap(Self::clone_failure_msg(uri, into_dir))
                     .map(|_| ())
             })
     }
 
-    fn pull_rebase(path: &PathBuf) -> anyhow::Result<()> {
+    fn clone_failure_msg(uri: &str, into_dir: &PathBuf) -> String {
+        let base = format!("Failed cloning {} into {:?}", uri, into_dir);
+        if GitRemote::from(uri).is_ssh() {
+            format!("{} — ensure an SSH agent is running with credentials for this host", base)
+        } else {
+            base
+        }
+    }
+
+    fn pull_rebase(path: &PathBuf, url: &str) -> anyhow::Result<()> {
         debug!("Updating {} at {:?}", Self::NAME, path);
-        Git::pull_rebase(path)
+        resolve(url).0.pull_rebase(path)
     }
 
     fn find(hash: String) -> Option<PathBuf> {
@@ -61,7 +74,10 @@ pub trait Upgradable {
         Zr::home()
             .map(|it| it.join(Self::INSTALL_DIR))
             .map(|it| {
-                if !it.exists() { it.create_dir_all_or_fail() }
+                if !it.exists() {
+                    Privilege::drop_to_real_user();
+                    it.create_dir_all_or_fail();
+                }
                 it
             })
     }
@@ -0,0 +1,386 @@
+//! A small self-contained parser/evaluator for Cargo's `cfg(...)` mini-language,
+//! reused wherever `zr` needs to gate something (a flag, a file, a section) behind a
+//! platform or feature predicate.
+use std::collections::HashMap;
+
+use serde_json::{Map, Value};
+
+/// A single predicate: either a bare name (`unix`) or a `key = "value"` pair
+/// (`target_os = "macos"`).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Cfg {
+    Name(String),
+    KeyValue(String, String),
+}
+
+/// A boolean expression over `Cfg` predicates: `Value`, `not(..)`, `all(..)`, `any(..)`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum CfgExpr {
+    Value(Cfg),
+    Not(Box<CfgExpr>),
+    All(Vec<CfgExpr>),
+    Any(Vec<CfgExpr>),
+}
+
+impl CfgExpr {
+    /// Parses a cfg-expression such as `any(unix, target_os = "macos")`.
+    /// Returns `None` on any malformed input rather than partially parsing it.
+    pub fn parse(input: &str) -> Option<Self> {
+        let tokens = Tokenizer::new(input).tokenize()?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.expr()?;
+        if parser.pos == parser.tokens.len() { Some(expr) } else { None }
+    }
+
+    /// Evaluates the expression against a set of active predicates: a bare name is
+    /// true when present as a key (regardless of its value), a `key = "value"` pair
+    /// is true only when `active[key] == value`. Unknown keys evaluate to false.
+    pub fn eval(&self, active: &HashMap<String, String>) -> bool {
+        match self {
+            CfgExpr::Value(Cfg::Name(name)) => active.contains_key(name.as_str()),
+            CfgExpr::Value(Cfg::KeyValue(key, value)) => active.get(key.as_str()).map(|it| it == value).unwrap_or(false),
+            CfgExpr::Not(expr) => !expr.eval(active),
+            // empty all() is vacuously true
+            CfgExpr::All(exprs) => exprs.iter().all(|it| it.eval(active)),
+            // empty any() is vacuously false
+            CfgExpr::Any(exprs) => exprs.iter().any(|it| it.eval(active)),
+        }
+    }
+}
+
+/// Builds the active predicate set for the host platform from `std::env::consts`:
+/// `target_os`/`target_arch`/`target_family` as key/value pairs, plus the matching
+/// bare `unix`/`windows` name for the family, mirroring what Cargo's cfg evaluator
+/// exposes to `[target.'cfg(...)']` sections.
+pub fn host_platform_active() -> HashMap<String, String> {
+    let mut active = HashMap::new();
+    active.insert(String::from("target_os"), String::from(std::env::consts::OS));
+    active.insert(String::from("target_arch"), String::from(std::env::consts::ARCH));
+    active.insert(String::from("target_family"), String::from(std::env::consts::FAMILY));
+    active.insert(String::from(std::env::consts::FAMILY), String::from("true"));
+    active
+}
+
+/// Builds the active predicate set template files are gated against: [`host_platform_active`]
+/// plus every already-resolved arg as a `key = "value"` entry, mirroring the same `Map<String,
+/// Value>` [`crate::data::arg_path::ArgSeparator`] decorates. A `false`-valued flag is dropped
+/// rather than stringified, so `not(some-flag)` behaves the same as the flag being entirely
+/// absent.
+pub fn active_for_data(args: &Map<String, Value>) -> HashMap<String, String> {
+    let mut active = host_platform_active();
+    for (key, value) in args {
+        match value {
+            Value::Bool(false) => continue,
+            Value::String(s) => { active.insert(key.clone(), s.clone()); }
+            other => { active.insert(key.clone(), other.to_string()); }
+        }
+    }
+    active
+}
+
+/// The marker a template file name carries to gate its generation: `.cfg(<expr>)` appearing
+/// anywhere in the name, e.g. `name.cfg(any(unix, feature = "cli")).txt`.
+const CFG_MARKER: &str = ".cfg(";
+
+/// Splits a template file name into its cfg-stripped form and the gating expression it
+/// carried, if any. Returns `(name, None)` unchanged when no marker is present, or when the
+/// marker's parentheses never close. A marker with balanced parentheses whose expression
+/// fails to parse still has its text stripped but yields `(name, None)` too, since the caller can't distinguish "never gated" from "gated but invalid" by
+/// expression alone; use [`has_cfg_marker`] first when that distinction matters.
+pub fn extract_marker(name: &str) -> (String, Option<CfgExpr>) {
+    let start = match name.find(CFG_MARKER) {
+        Some(start) => start,
+        None => return (name.to_string(), None),
+    };
+    let open = start + CFG_MARKER.len() - 1;
+    let end = match matching_paren(name, open) {
+        Some(end) => end,
+        None => return (name.to_string(), None),
+    };
+    let cleaned = format!("{}{}", &name[..start], &name[end + 1..]);
+    (cleaned, CfgExpr::parse(&name[open + 1..end]))
+}
+
+/// Whether `name` carries a `.cfg(...)` marker at all, regardless of whether its
+/// expression parses.
+pub fn has_cfg_marker(name: &str) -> bool {
+    name.contains(CFG_MARKER)
+}
+
+fn matching_paren(text: &str, open: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in text.char_indices().skip(open) {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 { return Some(i); }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Eq,
+    LParen,
+    RParen,
+    Comma,
+}
+
+struct Tokenizer<'a> {
+    chars: std::iter::Peekable<std::str::Chars<'a>>,
+}
+
+impl<'a> Tokenizer<'a> {
+    fn new(input: &'a str) -> Self {
+        Self { chars: input.chars().peekable() }
+    }
+
+    fn tokenize(mut self) -> Option<Vec<Token>> {
+        let mut tokens = vec![];
+        while let Some(&c) = self.chars.peek() {
+            match c {
+                ' ' | '\t' | '\n' | '\r' => { self.chars.next(); }
+                '(' => { self.chars.next(); tokens.push(Token::LParen); }
+                ')' => { self.chars.next(); tokens.push(Token::RParen); }
+                ',' => { self.chars.next(); tokens.push(Token::Comma); }
+                '=' => { self.chars.next(); tokens.push(Token::Eq); }
+                '"' => tokens.push(self.string()?),
+                c if c.is_alphanumeric() || c == '_' || c == '-' || c == '.' => tokens.push(self.ident()),
+                _ => return None,
+            }
+        }
+        Some(tokens)
+    }
+
+    fn ident(&mut self) -> Token {
+        let mut value = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if c.is_alphanumeric() || c == '_' || c == '-' || c == '.' {
+                value.push(c);
+                self.chars.next();
+            } else { break; }
+        }
+        Token::Ident(value)
+    }
+
+    fn string(&mut self) -> Option<Token> {
+        self.chars.next(); // opening quote
+        let mut value = String::new();
+        loop {
+            match self.chars.next()? {
+                '"' => return Some(Token::Str(value)),
+                '\\' => value.push(self.chars.next()?),
+                c => value.push(c),
+            }
+        }
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> { self.tokens.get(self.pos) }
+
+    fn bump(&mut self) -> Option<&Token> {
+        let token = self.tokens.get(self.pos);
+        self.pos += 1;
+        token
+    }
+
+    fn expr(&mut self) -> Option<CfgExpr> {
+        match self.peek()?.clone() {
+            Token::Ident(name) if name == "not" => {
+                self.bump();
+                self.expect(Token::LParen)?;
+                let inner = self.expr()?;
+                self.expect(Token::RParen)?;
+                Some(CfgExpr::Not(Box::new(inner)))
+            }
+            Token::Ident(name) if name == "all" => {
+                self.bump();
+                Some(CfgExpr::All(self.list()?))
+            }
+            Token::Ident(name) if name == "any" => {
+                self.bump();
+                Some(CfgExpr::Any(self.list()?))
+            }
+            Token::Ident(name) => {
+                self.bump();
+                if self.peek() == Some(&Token::Eq) {
+                    self.bump();
+                    match self.bump()?.clone() {
+                        Token::Str(value) => Some(CfgExpr::Value(Cfg::KeyValue(name, value))),
+                        _ => None,
+                    }
+                } else {
+                    Some(CfgExpr::Value(Cfg::Name(name)))
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Parses a parenthesized, comma separated list of expressions (possibly empty).
+    fn list(&mut self) -> Option<Vec<CfgExpr>> {
+        self.expect(Token::LParen)?;
+        let mut exprs = vec![];
+        if self.peek() != Some(&Token::RParen) {
+            exprs.push(self.expr()?);
+            while self.peek() == Some(&Token::Comma) {
+                self.bump();
+                exprs.push(self.expr()?);
+            }
+        }
+        self.expect(Token::RParen)?;
+        Some(exprs)
+    }
+
+    fn expect(&mut self, token: Token) -> Option<()> {
+        if self.bump()? == &token { Some(()) } else { None }
+    }
+}
+
+#[cfg(test)]
+mod cfg_expr_tests {
+    use std::iter::FromIterator;
+
+    use super::*;
+
+    fn active(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        HashMap::from_iter(pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())))
+    }
+
+    #[test]
+    fn should_eval_bare_name() {
+        let expr = CfgExpr::parse("unix").unwrap();
+        assert!(expr.eval(&active(&[("unix", "")])));
+        assert!(!expr.eval(&active(&[])));
+    }
+
+    #[test]
+    fn should_eval_key_value() {
+        let expr = CfgExpr::parse(r#"target_os = "macos""#).unwrap();
+        assert!(expr.eval(&active(&[("target_os", "macos")])));
+        assert!(!expr.eval(&active(&[("target_os", "linux")])));
+    }
+
+    #[test]
+    fn should_eval_not() {
+        let expr = CfgExpr::parse("not(windows)").unwrap();
+        assert!(expr.eval(&active(&[("unix", "")])));
+        assert!(!expr.eval(&active(&[("windows", "")])));
+    }
+
+    #[test]
+    fn should_eval_all() {
+        let expr = CfgExpr::parse(r#"all(unix, target_arch = "x86_64")"#).unwrap();
+        assert!(expr.eval(&active(&[("unix", ""), ("target_arch", "x86_64")])));
+        assert!(!expr.eval(&active(&[("unix", ""), ("target_arch", "arm")])));
+    }
+
+    #[test]
+    fn should_eval_any() {
+        let expr = CfgExpr::parse(r#"any(windows, target_os = "macos")"#).unwrap();
+        assert!(expr.eval(&active(&[("target_os", "macos")])));
+        assert!(expr.eval(&active(&[("windows", "")])));
+        assert!(!expr.eval(&active(&[("target_os", "linux")])));
+    }
+
+    #[test]
+    fn empty_all_should_be_true() {
+        assert!(CfgExpr::parse("all()").unwrap().eval(&active(&[])));
+    }
+
+    #[test]
+    fn empty_any_should_be_false() {
+        assert!(!CfgExpr::parse("any()").unwrap().eval(&active(&[])));
+    }
+
+    #[test]
+    fn should_unescape_quotes_in_string() {
+        let expr = CfgExpr::parse(r#"feature = "a\"b""#).unwrap();
+        assert!(expr.eval(&active(&[("feature", "a\"b")])));
+    }
+
+    #[test]
+    fn should_fail_on_malformed_expression() {
+        assert!(CfgExpr::parse("all(unix").is_none());
+        assert!(CfgExpr::parse("target_os = ").is_none());
+    }
+
+    mod active_for_data {
+        use std::iter::FromIterator;
+
+        use super::*;
+
+        fn args(pairs: Vec<(&str, Value)>) -> Map<String, Value> {
+            Map::from_iter(pairs.into_iter().map(|(k, v)| (k.to_string(), v)))
+        }
+
+        #[test]
+        fn should_include_host_platform() {
+            let active = active_for_data(&Map::new());
+            assert_eq!(active.get("target_os").map(String::as_str), Some(std::env::consts::OS));
+        }
+
+        #[test]
+        fn should_include_string_arg_as_key_value() {
+            let active = active_for_data(&args(vec![("feature", Value::from("db"))]));
+            assert_eq!(active.get("feature").map(String::as_str), Some("db"));
+        }
+
+        #[test]
+        fn should_drop_false_flag() {
+            let active = active_for_data(&args(vec![("dry", Value::from(false))]));
+            assert!(active.get("dry").is_none());
+        }
+
+        #[test]
+        fn should_keep_true_flag_as_string() {
+            let active = active_for_data(&args(vec![("dry", Value::from(true))]));
+            assert_eq!(active.get("dry").map(String::as_str), Some("true"));
+        }
+    }
+
+    mod marker {
+        use super::*;
+
+        #[test]
+        fn should_leave_plain_name_unchanged() {
+            let (name, expr) = extract_marker("README.md");
+            assert_eq!(name, "README.md");
+            assert!(expr.is_none());
+        }
+
+        #[test]
+        fn should_strip_marker_and_parse_expression() {
+            let (name, expr) = extract_marker(r#"name.cfg(any(unix, feature = "cli")).txt"#);
+            assert_eq!(name, "name.txt");
+            assert_eq!(expr, Some(CfgExpr::Any(vec![
+                CfgExpr::Value(Cfg::Name(String::from("unix"))),
+                CfgExpr::Value(Cfg::KeyValue(String::from("feature"), String::from("cli"))),
+            ])));
+        }
+
+        #[test]
+        fn should_detect_marker_presence_even_when_malformed() {
+            assert!(has_cfg_marker("name.cfg(not(.txt"));
+            let (_, expr) = extract_marker("name.cfg(not(.txt");
+            assert!(expr.is_none());
+        }
+
+        #[test]
+        fn should_not_detect_marker_when_absent() {
+            assert!(!has_cfg_marker("plain.txt"));
+        }
+    }
+}
@@ -3,6 +3,7 @@ use std::fmt::Display;
 use colored::Colorize;
 
 use crate::console::emoji;
+use crate::utils::messages::Messages;
 
 pub trait ErrorExt<T> {
     const UNEXPECTED_ERROR: &'static str = "An unexpected error occurred.";
@@ -20,6 +21,22 @@ pub trait ErrorExt<T> {
     fn unexpected_failure(self) -> T;
     /// Execute fun if Ok or Some then continue
     fn then<F>(self, fun: F) -> Self where F: Fn(&T);
+
+    /// Localized variant of [`Self::fail`]: renders `id` through the active [`Messages`]
+    /// catalog, interpolating `args`, before failing with it
+    fn fail_msg(self, id: &str, args: &[&str]) -> T where Self: Sized {
+        self.fail(Messages::get().render(id, args))
+    }
+
+    /// Localized variant of [`Self::warn`]
+    fn warn_msg(self, id: &str, args: &[&str]) where Self: Sized {
+        self.warn(Messages::get().render(id, args))
+    }
+
+    /// Localized variant of [`Self::debug`]
+    fn debug_msg(self, id: &str, args: &[&str]) where Self: Sized {
+        self.debug(Messages::get().render(id, args))
+    }
 }
 
 impl<T, E: Display> ErrorExt<T> for Result<T, E> {
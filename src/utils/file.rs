@@ -99,14 +99,36 @@ pub trait PathExt where Self: AsRef<Path> + Debug {
 
     /// Writes to file with new line at the end of each line
     fn write_to(&self, value: &str) {
-        self.write_to_file(&mut self.open_replace(), value)
+        self.write_atomic(vec![value.to_string()])
     }
 
     /// Writes to file with new line at the end of each line
     fn write(&self, lines: Vec<String>) {
-        let mut file = self.open_replace();
-        lines.iter()
-            .for_each(|line| { self.write_to_file(&mut file, line) });
+        self.write_atomic(lines)
+    }
+
+    /// Writes `lines` to a temp file created alongside `self` (same directory, so the final
+    /// rename stays on one filesystem and is atomic), flushes it, then renames it over `self`.
+    /// A crash or error mid-write can at worst leave the temp file behind, never a half-written
+    /// or empty target ; the temp file is removed on any failure before it's propagated.
+    fn write_atomic(&self, lines: Vec<String>) {
+        let target = self.as_ref();
+        let dir = target.parent().unwrap_or_else(|| Path::new("."));
+        let name = target.file_name_str().fail(format!("Failed resolving file name of {:?}", target));
+        let tmp = dir.join(format!("{}.tmp", name));
+        let result = File::create(&tmp)
+            .wrap()
+            .and_then(|mut file| {
+                lines.iter()
+                    .try_for_each(|line| file.write_fmt(format_args!("{}\n", line)))
+                    .wrap()?;
+                file.flush().wrap()
+            })
+            .and_then(|_| fs::rename(&tmp, target).wrap());
+        if result.is_err() {
+            let _ = fs::remove_file(&tmp);
+        }
+        result.fail(format!("Failed atomically writing to {:?}", target));
     }
 
     /// Opens a file with read only access
@@ -175,6 +197,50 @@ pub trait PathExt where Self: AsRef<Path> + Debug {
             .fail(format!("Failed copying all files from {:?} to {:?}", self, to));
     }
 
+    /// Recursively walks every file under `self`, returning those whose path relative to
+    /// `self` matches the glob `pattern`. `*` matches any run of characters (including
+    /// path separators, so it behaves like `**` in tools that distinguish the two) and
+    /// `?` matches exactly one.
+    fn walk_glob(&self, pattern: &str) -> Vec<PathBuf> {
+        Self::walk(self.as_ref()).into_iter()
+            .filter(|path| {
+                path.strip_prefix(self.as_ref()).ok()
+                    .and_then(|rel| rel.to_str())
+                    .map(|rel| Self::glob_match(pattern, rel))
+                    .unwrap_or(false)
+            })
+            .collect_vec()
+    }
+
+    /// All file paths (not directories) recursively under `dir`, depth-first
+    fn walk(dir: &Path) -> Vec<PathBuf> {
+        dir.read_dir().ok()
+            .map(|entries| entries.filter_map(|it| it.ok()).map(|it| it.path())
+                .flat_map(|path| if path.is_dir() { Self::walk(&path) } else { vec![path] })
+                .collect_vec())
+            .unwrap_or_default()
+    }
+
+    /// Classic recursive wildcard matcher: `*` matches any run of characters and `?`
+    /// matches exactly one. No distinct `**` segment-crossing form ; `*` already crosses
+    /// path separators since matching runs against the whole relative path string.
+    fn glob_match(pattern: &str, text: &str) -> bool {
+        Self::glob_match_bytes(pattern.as_bytes(), text.as_bytes())
+    }
+
+    fn glob_match_bytes(pattern: &[u8], text: &[u8]) -> bool {
+        match (pattern.first(), text.first()) {
+            (None, None) => true,
+            (Some(b'*'), _) => {
+                Self::glob_match_bytes(&pattern[1..], text)
+                    || (!text.is_empty() && Self::glob_match_bytes(pattern, &text[1..]))
+            }
+            (Some(b'?'), Some(_)) => Self::glob_match_bytes(&pattern[1..], &text[1..]),
+            (Some(p), Some(t)) if p == t => Self::glob_match_bytes(&pattern[1..], &text[1..]),
+            _ => false,
+        }
+    }
+
     /// Reads each line of the file and returns them as a vec of string
     fn lines(&self) -> Vec<String> {
         self.open_read()
@@ -316,4 +382,26 @@ mod file_ext_tests {
         assert!(home.find("unknown").is_none());
         assert!(home.find("unknown.txt").is_none());
     }
+
+    #[test]
+    fn should_walk_glob_recursively() {
+        let root = MockFs::home().join("walk-glob");
+        let src_main = root.join("src").join("main");
+        src_main.create_dir_all_or_fail();
+        let main_rs = src_main.join("main.rs");
+        let lib_rs = src_main.join("lib.rs");
+        let readme = root.join("README.md");
+        main_rs.create().unwrap();
+        lib_rs.create().unwrap();
+        readme.create().unwrap();
+
+        let rs_files = root.walk_glob("*.rs");
+        assert_eq!(rs_files.len(), 2);
+        assert!(rs_files.contains(&main_rs));
+        assert!(rs_files.contains(&lib_rs));
+
+        assert_eq!(root.walk_glob("src/*/main.rs"), vec![main_rs]);
+        assert_eq!(root.walk_glob("*.md"), vec![readme]);
+        assert!(root.walk_glob("*.toml").is_empty());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,64 @@
+//! Levenshtein edit distance, used to power "did you mean '<x>'?" suggestions when a
+//! template name or subcommand doesn't match anything known, mirroring cargo's
+//! `lev_distance` helper.
+use itertools::Itertools;
+
+/// Computes the edit distance between `a` and `b` keeping a single DP row.
+pub fn distance(a: &str, b: &str) -> usize {
+    let a = a.chars().collect_vec();
+    let b = b.chars().collect_vec();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, &a_char) in a.iter().enumerate() {
+        let mut cur = vec![0; b.len() + 1];
+        cur[0] = i + 1;
+        for (j, &b_char) in b.iter().enumerate() {
+            let substitution_cost = if a_char != b_char { 1 } else { 0 };
+            cur[j + 1] = (prev[j + 1] + 1).min(cur[j] + 1).min(prev[j] + substitution_cost);
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
+
+/// Finds the candidate closest to `target`, only when its distance stays below the
+/// `max(len)/3 + 1` threshold so unrelated typos stay silent.
+pub fn closest_match<'a>(target: &str, candidates: impl IntoIterator<Item=&'a str>) -> Option<&'a str> {
+    candidates.into_iter()
+        .map(|candidate| (candidate, distance(target, candidate)))
+        .filter(|(candidate, dist)| *dist < target.len().max(candidate.len()) / 3 + 1)
+        .min_by_key(|(_, dist)| *dist)
+        .map(|(candidate, _)| candidate)
+}
+
+#[cfg(test)]
+mod levenshtein_tests {
+    use super::*;
+
+    #[test]
+    fn should_find_exact_match_distance_zero() {
+        assert_eq!(distance("rust-app", "rust-app"), 0);
+    }
+
+    #[test]
+    fn should_count_single_substitution() {
+        assert_eq!(distance("rust-app", "rust-apq"), 1);
+    }
+
+    #[test]
+    fn should_count_insertions_and_deletions() {
+        assert_eq!(distance("new", "ne"), 1);
+        assert_eq!(distance("new", "news"), 1);
+    }
+
+    #[test]
+    fn should_suggest_closest_candidate() {
+        let candidates = vec!["rust-app", "java-app", "python-app"];
+        assert_eq!(closest_match("rust-apq", candidates), Some("rust-app"));
+    }
+
+    #[test]
+    fn should_not_suggest_when_too_far() {
+        let candidates = vec!["rust-app", "java-app"];
+        assert_eq!(closest_match("totally-unrelated", candidates), None);
+    }
+}
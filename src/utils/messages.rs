@@ -0,0 +1,108 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::utils::marshall::Tomlable;
+use crate::utils::zr::Zr;
+
+/// Catalog of localized, interpolatable message templates keyed by a short message id
+///
+/// Loaded from `messages.<locale>.toml` under [`Zr::home()`], with placeholders written
+/// `{0}`, `{1}`, ... substituted positionally by [`Messages::render`]. The active locale is
+/// read from `$LC_MESSAGES`/`$LANG` (e.g. `fr_FR.UTF-8` resolves to `fr`), falling back to the
+/// built-in `"C"` catalog when unset or the locale file doesn't exist, the same way forge-build
+/// loads translations without failing on the default locale.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Messages {
+    entries: BTreeMap<String, String>,
+}
+
+impl Messages {
+    const DEFAULT_LOCALE: &'static str = "C";
+    const FILE_PREFIX: &'static str = "messages";
+    const FILE_EXTENSION: &'static str = "toml";
+
+    /// Memoized catalog for the active locale
+    pub fn get() -> Messages { cached_messages() }
+
+    fn load() -> Messages {
+        Self::file_for(&Self::active_locale())
+            .filter(|it| it.exists())
+            .map(Messages::from_file_or_fail)
+            .unwrap_or_else(Self::default_catalog)
+    }
+
+    /// Built-in `"C"` catalog used when no locale file is installed, so messages always
+    /// render in plain English even on a machine with no translations set up
+    fn default_catalog() -> Messages {
+        let entries = [
+            ("git_init_failed", "Failed initializing git in {0}"),
+            ("hg_init_failed", "Failed initializing hg in {0}"),
+            ("vcs_unavailable", "'{0}' not found on PATH, skipping VCS initialization in {1}"),
+        ].iter().map(|&(id, template)| (id.to_string(), template.to_string())).collect();
+        Messages { entries }
+    }
+
+    /// `$LC_MESSAGES`/`$LANG` are usually POSIX locale strings like `fr_FR.UTF-8`; only the
+    /// language subtag before the first `_`/`.` is used to pick a catalog file
+    fn active_locale() -> String {
+        env::var("LC_MESSAGES").or_else(|_| env::var("LANG")).ok()
+            .and_then(|it| it.split(|c| c == '_' || c == '.').next().map(String::from))
+            .filter(|it| !it.is_empty())
+            .unwrap_or_else(|| Self::DEFAULT_LOCALE.to_string())
+    }
+
+    fn file_for(locale: &str) -> Option<PathBuf> {
+        Zr::home().map(|home| home.join(format!("{}.{}.{}", Self::FILE_PREFIX, locale, Self::FILE_EXTENSION)))
+    }
+
+    /// Renders message `id`'s template, substituting `{0}`, `{1}`, ... with `args` in order.
+    /// Falls back to the raw id when the catalog has no entry for it, so an untranslated or
+    /// unknown id still surfaces something rather than panicking.
+    pub fn render(&self, id: &str, args: &[&str]) -> String {
+        let template = self.entries.get(id).cloned().unwrap_or_else(|| id.to_string());
+        args.iter().enumerate()
+            .fold(template, |msg, (i, arg)| msg.replace(&format!("{{{}}}", i), arg))
+    }
+}
+
+impl Tomlable for Messages {}
+
+cached! {
+    MESSAGES;
+    fn cached_messages() -> Messages = { Messages::load() }
+}
+
+#[cfg(test)]
+mod messages_tests {
+    use super::*;
+
+    #[test]
+    fn should_render_known_entry_with_interpolation() {
+        let messages = Messages { entries: vec![("clone_failed".to_string(), "Failed cloning {0}".to_string())].into_iter().collect() };
+        assert_eq!(messages.render("clone_failed", &["https://example.org/repo.git"]), "Failed cloning https://example.org/repo.git");
+    }
+
+    #[test]
+    fn should_fall_back_to_id_when_entry_unknown() {
+        let messages = Messages::default();
+        assert_eq!(messages.render("unknown_id", &[]), "unknown_id");
+    }
+
+    #[test]
+    fn should_resolve_language_subtag_from_posix_locale() {
+        env::set_var("LC_MESSAGES", "fr_FR.UTF-8");
+        assert_eq!(Messages::active_locale(), "fr");
+        env::remove_var("LC_MESSAGES");
+    }
+
+    #[test]
+    fn should_default_locale_when_unset() {
+        env::remove_var("LC_MESSAGES");
+        env::remove_var("LANG");
+        assert_eq!(Messages::active_locale(), Messages::DEFAULT_LOCALE);
+    }
+}
@@ -6,3 +6,7 @@ pub mod anyhow_err;
 /// Core function mainly related to local environment
 pub mod user;
 pub mod zr;
+pub mod cfg_expr;
+pub mod levenshtein;
+pub mod messages;
+pub mod privilege;
@@ -0,0 +1,93 @@
+#[cfg(unix)]
+use std::env;
+#[cfg(unix)]
+use std::ffi::CString;
+use std::path::Path;
+
+/// Detects and neutralizes the euid≠ruid gap a setuid-installed `zr` runs under, so a
+/// site-wide installation doesn't leave root-owned template caches and generated projects
+/// behind for the invoking user.
+pub struct Privilege;
+
+impl Privilege {
+    /// Whether the process was invoked with elevated privileges, i.e. its effective user
+    /// differed from the real one at startup. Memoized on first call (see `cached_is_setuid`)
+    /// rather than re-checking `geteuid()` live: `drop_to_real_user` permanently lowers the
+    /// effective uid, so a live check would flip from true to false the moment privileges are
+    /// dropped, making every `chown_to_real_user` call after that point a silent no-op.
+    #[cfg(unix)]
+    pub fn is_setuid() -> bool {
+        cached_is_setuid()
+    }
+
+    #[cfg(windows)]
+    pub fn is_setuid() -> bool { false }
+
+    /// Resolves the real invoking user's uid: `$SUDO_USER` when set (sudo/setuid-root
+    /// invocation), otherwise falls back to the process' real uid
+    #[cfg(unix)]
+    pub fn real_uid() -> u32 {
+        env::var("SUDO_USER").ok()
+            .and_then(|name| Self::uid_for(&name))
+            .unwrap_or_else(|| unsafe { libc::getuid() })
+    }
+
+    #[cfg(windows)]
+    pub fn real_uid() -> u32 { 0 }
+
+    #[cfg(unix)]
+    fn uid_for(username: &str) -> Option<u32> {
+        let name = CString::new(username).ok()?;
+        let passwd = unsafe { libc::getpwnam(name.as_ptr()) };
+        if passwd.is_null() { None } else { Some(unsafe { (*passwd).pw_uid }) }
+    }
+
+    /// Drops effective privileges back to the real invoking user for the remainder of the
+    /// process, when running setuid. Best-effort: a failing `seteuid` is logged rather than
+    /// fatal, since `zr` still functions (it just leaves root-owned artifacts behind)
+    #[cfg(unix)]
+    pub fn drop_to_real_user() {
+        if Self::is_setuid() {
+            let uid = Self::real_uid();
+            if unsafe { libc::seteuid(uid) } != 0 {
+                warn!("Failed dropping privileges to uid {}", uid);
+            }
+        }
+    }
+
+    #[cfg(windows)]
+    pub fn drop_to_real_user() {}
+
+    /// Recursively `chown`s `path` back to the real invoking user when running setuid, so
+    /// trees created while euid was root (template caches, generated projects) are still
+    /// editable by the user who actually ran `zr`
+    #[cfg(unix)]
+    pub fn chown_to_real_user(path: &Path) {
+        if !Self::is_setuid() {
+            return;
+        }
+        Self::chown_recursive(path, Self::real_uid());
+    }
+
+    #[cfg(windows)]
+    pub fn chown_to_real_user(_path: &Path) {}
+
+    #[cfg(unix)]
+    fn chown_recursive(path: &Path, uid: u32) {
+        if let Some(c_path) = path.to_str().and_then(|it| CString::new(it).ok()) {
+            unsafe { libc::chown(c_path.as_ptr(), uid, u32::MAX) };
+        }
+        if path.is_dir() {
+            if let Ok(entries) = std::fs::read_dir(path) {
+                entries.filter_map(Result::ok)
+                    .for_each(|entry| Self::chown_recursive(&entry.path(), uid));
+            }
+        }
+    }
+}
+
+#[cfg(unix)]
+cached! {
+    IS_SETUID;
+    fn cached_is_setuid() -> bool = { unsafe { libc::geteuid() != libc::getuid() } }
+}